@@ -1,26 +1,155 @@
-use crate::fileflow::action::database_command::{fast_insert, optimized_insert};
+use crate::fileflow::action::database_command::{
+    append_insert, bulk_insert, copy_insert, fast_insert, get_boolean_columns, get_column_types,
+    normalize_booleans, observe_record, optimized_insert, upsert_insert,
+};
 use crate::fileflow::database::connection::Connection;
+use crate::fileflow::database::database_actions::{
+    begin_import, commit_import, drop_existing_tables, execute_query, get_table_columns,
+    get_temporary_table_name, rollback_import,
+};
+use crate::fileflow::database::sql_builder::{
+    build_add_column_sql, build_alter_column_types_sql, build_copy_from_stdin_sql,
+    build_create_table_sql, build_create_table_with_primary_key_sql,
+    build_create_with_fixed_size_sql, build_prepared_statement_sql,
+};
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::enumeration::history_operation::HistoryOperation;
 use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::enumeration::optimized_strategy::OptimizedStrategy;
+use crate::fileflow::stuct::column_profile::ColumnProfile;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
+use crate::fileflow::stuct::file_section::FileSection;
+use crate::fileflow::stuct::history_entry::HistoryEntry;
+use crate::fileflow::stuct::history_filter::HistoryFilter;
+use crate::fileflow::stuct::history_log::HistoryLog;
+use crate::fileflow::stuct::import_checkpoint::ImportCheckpoint;
+use crate::fileflow::stuct::import_context::ImportContext;
+use crate::fileflow::stuct::import_preview::ImportPreview;
+use crate::fileflow::stuct::import_report::ImportReport;
 use crate::fileflow::stuct::insert_config::InsertConfig;
+use crate::fileflow::stuct::job_registry::{JobGuard, JobRegistry};
+use crate::fileflow::stuct::progress::EventProgressListener;
 use crate::fileflow::stuct::save_config::SaveConfig;
+use crate::fileflow::stuct::schema_drift::SchemaDrift;
 use crate::fileflow::stuct::string_formater::StringFormatter;
-use crate::fileflow::utils::constants::DATABASE_CONFIG_FILE;
-use crate::fileflow::utils::csv_utils::{find_separator, read_first_line};
+use crate::fileflow::stuct::table_column::TableColumn;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+use crate::fileflow::utils::constants::{DATABASE_CONFIG_FILE, IMPORT_PROGRESS_EVENT};
+use crate::fileflow::utils::csv_utils::{
+    get_file_fingerprint, get_file_size, read_column_names, read_header_names,
+    sniff_dialect_from_reader,
+};
 use crate::fileflow::utils::fileflowlib::{get_all_saved_configs, save_config};
-use csv::{Reader, ReaderBuilder};
-use std::fs::{File, Metadata};
+use csv::{Reader, StringRecord};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
-use tauri::{command, State};
+use tauri::{command, AppHandle, State};
 use tokio::sync::Mutex;
 
 pub struct DatabaseState(pub Mutex<Option<Connection>>);
 
 #[command]
 pub async fn insert_csv_data(
+    app: AppHandle,
     state: State<'_, Arc<DatabaseState>>,
+    jobs: State<'_, Arc<JobRegistry>>,
     csv: InsertConfig,
-) -> Result<String, String> {
+) -> Result<ImportReport, String> {
+    // Registered first, so that the job can be cancelled while it waits for the connection
+    let job: JobGuard = jobs
+        .register(&csv.job_id)
+        .map_err(|e| format!("Error: {e}"))?;
+    // The whole file is digested once, for the history and the checkpoint, and only when a checkpoint is saved
+    let fingerprint: Option<String> = if csv.is_checkpointed() {
+        get_file_fingerprint(&csv.file_path)
+            .inspect_err(|e| eprintln!("Error: Failed to fingerprint the file: {e}"))
+            .ok()
+    } else {
+        None
+    };
+    let checkpoint: Option<ImportCheckpoint> = fingerprint.as_deref().map(|fingerprint| {
+        ImportCheckpoint::new(&csv.file_path, fingerprint, &csv.get_qualified_table_name())
+    });
+    let entry: HistoryEntry = new_import_entry(&state, &csv, fingerprint).await;
+    let start: Instant = Instant::now();
+    let result: Result<ImportReport, String> =
+        import_csv(app.clone(), &state, &job, csv, checkpoint).await;
+    HistoryLog::record(&app, &entry.with_import_result(&result, start.elapsed()));
+    result
+}
+
+/// Resume an interrupted import from the checkpoint saved next to its csv file, without inserting the committed rows again
+#[command]
+pub async fn resume_import(
+    app: AppHandle,
+    state: State<'_, Arc<DatabaseState>>,
+    jobs: State<'_, Arc<JobRegistry>>,
+    csv: InsertConfig,
+) -> Result<ImportReport, String> {
+    if !csv.is_resumable() {
+        return Err("Error: Only the fast, append and upsert modes can be resumed, outside of a transaction".into());
+    }
+    let job: JobGuard = jobs
+        .register(&csv.job_id)
+        .map_err(|e| format!("Error: {e}"))?;
+
+    let checkpoint: ImportCheckpoint = ImportCheckpoint::load(&csv.file_path)
+        .ok_or_else(|| format!("Error: No checkpoint found for {}", csv.file_path))?;
+
+    let fingerprint: String = checkpoint
+        .validate(&csv.get_qualified_table_name())
+        .map_err(|e| format!("Error: {e}"))?;
+
+    let entry: HistoryEntry = new_import_entry(&state, &csv, Some(fingerprint)).await;
+    let start: Instant = Instant::now();
+    let result: Result<ImportReport, String> =
+        import_csv(app.clone(), &state, &job, csv, Some(checkpoint)).await;
+    HistoryLog::record(&app, &entry.with_import_result(&result, start.elapsed()));
+    result
+}
+
+/// Whether a failed import can be resumed: its mode keeps the committed batches and a checkpoint was saved for its table
+#[command]
+pub async fn can_resume_import(csv: InsertConfig) -> Result<bool, String> {
+    if !csv.is_resumable() {
+        return Ok(false);
+    }
+    Ok(ImportCheckpoint::load(&csv.file_path)
+        .is_some_and(|checkpoint| checkpoint.table_name == csv.get_qualified_table_name()))
+}
+
+/// History entry of an import about to run, completed with its result once it returns
+async fn new_import_entry(
+    state: &DatabaseState,
+    csv: &InsertConfig,
+    fingerprint: Option<String>,
+) -> HistoryEntry {
+    let connection: String = state
+        .0
+        .lock()
+        .await
+        .as_ref()
+        .map(|connection| connection.get_db_config().get_connection_name())
+        .unwrap_or_default();
+    HistoryEntry::new(
+        HistoryOperation::Import,
+        &csv.file_path,
+        fingerprint,
+        &connection,
+        vec![csv.get_qualified_table_name()],
+        Some(csv.mode.clone()),
+    )
+}
+
+async fn import_csv(
+    app: AppHandle,
+    state: &DatabaseState,
+    job: &JobGuard<'_>,
+    csv: InsertConfig,
+    checkpoint: Option<ImportCheckpoint>,
+) -> Result<ImportReport, String> {
     let conn_guard = state.0.lock().await;
 
     if conn_guard.is_none() {
@@ -28,69 +157,655 @@ pub async fn insert_csv_data(
     }
 
     let connection: &Connection = conn_guard.as_ref().unwrap();
+    let schema: Option<&str> = csv.schema.as_deref();
+    let table_name: String = csv.get_qualified_table_name();
+
+    // A job cancelled while it waited for the connection stops before touching any table
+    if job.cancel_token.is_cancelled() {
+        return Err(format!(
+            "Cancelled: the import into {} was cancelled",
+            table_name
+        ));
+    }
+
+    // An all-or-nothing import runs on its own session so that every statement belongs to the same transaction
+    let transactional_connection: Option<Connection> = if csv.transactional {
+        let session: Connection = Connection::connect_transactional(connection.get_db_config())
+            .await
+            .map_err(|e| format!("Error: Failed to open a transactional connection: {e}"))?;
+        Some(session)
+    } else {
+        None
+    };
+    let connection: &Connection = transactional_connection.as_ref().unwrap_or(connection);
+
     let mut total_lines: u64 = 0; // Counter for the total number of lines inserted
     let start: Instant = Instant::now(); // Timer for the insertion process
 
-    let file: File = File::open(&csv.file_path).expect("Failed to open file");
-    let first_line: String = read_first_line(&csv.file_path).expect("Failed to read first line"); // Read the first line of the file to detect the separator
-    let separator: char = find_separator(&first_line).expect("Failed to find separator"); // Separator detection of the file
+    let open_file = || {
+        csv.open_file()
+            .map_err(|e| format!("Error: Failed to open file: {e}"))
+    };
+    let dialect: CsvDialect = detect_dialect(&csv)?;
+    let separator: char = dialect.separator;
 
-    let final_columns_name: Vec<String> = StringFormatter::get_formated_column_names(
-        &first_line
-            .split(separator)
-            .map(|s| StringFormatter::sanitize_column(s))
-            .collect::<Vec<String>>(),
-    );
+    let mut reader: Reader<FileSection> = dialect.get_reader_builder().from_reader(open_file()?);
+    let header_names: Vec<String> =
+        read_header_names(&mut reader, dialect.has_headers, &csv.column_names)
+            .map_err(|e| format!("Error: {e}"))?;
+    let final_columns_name: Vec<String> =
+        StringFormatter::get_formated_column_names(&header_names, &csv.db_driver);
 
-    let mut reader: Reader<File> = ReaderBuilder::new()
-        .delimiter(u8::try_from(separator).unwrap())
-        .has_headers(true)
-        .from_reader(file);
+    let mut context: ImportContext =
+        ImportContext::new(&csv.file_path, separator, csv.reject_format.clone())
+            .with_progress_listener(Box::new(EventProgressListener::new(
+                app,
+                IMPORT_PROGRESS_EVENT,
+            )))
+            .with_cancel_token(job.cancel_token.clone())
+            .with_file_section(reader.get_ref())
+            .with_workers(csv.workers)
+            .with_varchar_sizing(csv.get_varchar_sizing());
 
-    match csv.mode {
-        InsertionType::Fast => {
-            match fast_insert(
+    // Checkpoints are only saved for the imports whose committed batches survive an interruption,
+    // a checkpoint without any committed batch starts the import over
+    match checkpoint {
+        Some(checkpoint) if checkpoint.is_started() => {
+            checkpoint
+                .seek_reader(&mut reader)
+                .map_err(|e| format!("Error: {e}"))?;
+            context = context.with_checkpoint(checkpoint);
+        }
+        Some(checkpoint) => {
+            ImportCheckpoint::remove(&csv.file_path);
+            context = context.with_checkpoint(checkpoint);
+        }
+        // A checkpoint left by a previous import does not apply to this one
+        None => {
+            if csv.is_resumable() {
+                ImportCheckpoint::remove(&csv.file_path);
+            }
+            if csv.is_checkpointed() {
+                context.add_warning(
+                    "Failed to fingerprint the file, the import can't be resumed".into(),
+                );
+            }
+        }
+    }
+
+    let mut mode: InsertionType = csv.mode.clone();
+    let mut schema_drift: Option<SchemaDrift> = None;
+    let mut added_columns: Vec<(String, ColumnType)> = Vec::new();
+    if csv.add_new_columns {
+        let (drift, added_types): (SchemaDrift, Vec<ColumnType>) =
+            detect_table_drift(connection, &csv).await?;
+        if drift.table_exists {
+            // A renamed column is neither added nor matched, the rows could only be refused one by one
+            if !drift.renamed.is_empty() {
+                return Err(format!(
+                    "Error: The csv columns don't match the columns of {table_name}, rename them or set the column names: {}",
+                    drift.get_warnings().join(", ")
+                ));
+            }
+            added_columns = drift.added.iter().cloned().zip(added_types).collect();
+            for warning in drift.get_warnings() {
+                context.add_warning(warning);
+            }
+            // The existing table is kept, the rows are appended to it instead of recreating it
+            if matches!(mode, InsertionType::Fast | InsertionType::Optimized) {
+                mode = InsertionType::Append;
+            }
+        }
+        schema_drift = Some(drift);
+    }
+
+    let target_table_name: String = match &transactional_connection {
+        Some(session) => begin_import(session, &csv.db_driver, schema, &csv.table_name, &mode)
+            .await
+            .map_err(|e| format!("Error: Failed to start the import: {e}"))?,
+        None => csv.table_name.clone(),
+    };
+
+    // The columns are added once the import started, so that a rolled back import does not keep them
+    for (column, column_type) in added_columns.iter() {
+        let sql: String = build_add_column_sql(
+            &csv.db_driver,
+            schema,
+            &target_table_name,
+            column,
+            column_type,
+        );
+        if let Err(e) = execute_query(connection, &sql, "Failed to add column").await {
+            if let Some(session) = &transactional_connection {
+                if let Err(e) =
+                    rollback_import(session, &csv.db_driver, schema, &target_table_name).await
+                {
+                    eprintln!("Error: {e}");
+                }
+                session.disconnect();
+            }
+            return Err(format!("Error: {e}"));
+        }
+        context.record_ddl(&sql);
+    }
+
+    let result: Result<u32, String> = match mode {
+        InsertionType::Fast
+            if csv.db_driver == DatabaseEngine::Postgres && !context.is_resumed() =>
+        {
+            // A failed COPY aborts the running transaction, the savepoint keeps the INSERT fallback possible
+            if connection.is_transactional() {
+                if let Err(e) = execute_query(
+                    connection,
+                    "SAVEPOINT copy_insert",
+                    "Failed to create savepoint",
+                )
+                .await
+                {
+                    eprintln!("Error: {e}");
+                }
+            }
+            match copy_insert(
                 connection,
-                &mut reader,
+                open_file()?,
+                &dialect,
                 &final_columns_name,
-                &csv.table_name,
-                &csv.db_driver,
+                schema,
+                &target_table_name,
+                &mut context,
             )
             .await
             {
-                Ok(lines) => {
-                    total_lines += u64::from(lines);
-                }
-                Err(e) => {
-                    return Err(format!("Error: Failed to insert data: {}", e.to_string()));
+                Ok(lines) => Ok(lines),
+                Err(err) if context.is_cancelled() => Err(err),
+                Err(err) => {
+                    // COPY may be refused by the server or a proxy, the INSERT path still works
+                    eprintln!("Error: COPY failed, falling back to INSERT: {err}");
+                    context.add_warning(format!("COPY failed, falling back to INSERT: {err}"));
+                    if connection.is_transactional() {
+                        if let Err(e) = execute_query(
+                            connection,
+                            "ROLLBACK TO SAVEPOINT copy_insert",
+                            "Failed to rollback to savepoint",
+                        )
+                        .await
+                        {
+                            eprintln!("Error: {e}");
+                        }
+                    }
+                    fast_insert(
+                        connection,
+                        &mut reader,
+                        &final_columns_name,
+                        schema,
+                        &target_table_name,
+                        &csv.db_driver,
+                        &mut context,
+                    )
+                    .await
                 }
             }
         }
+        InsertionType::Fast
+            if csv.bulk_load
+                && matches!(
+                    csv.db_driver,
+                    DatabaseEngine::MySQL | DatabaseEngine::MariaDB
+                ) =>
+        {
+            bulk_insert(
+                connection,
+                &mut reader,
+                &final_columns_name,
+                schema,
+                &target_table_name,
+                &csv.db_driver,
+                &mut context,
+            )
+            .await
+        }
+        InsertionType::Fast => {
+            fast_insert(
+                connection,
+                &mut reader,
+                &final_columns_name,
+                schema,
+                &target_table_name,
+                &csv.db_driver,
+                &mut context,
+            )
+            .await
+        }
         InsertionType::Optimized => {
-            match optimized_insert(
+            optimized_insert(
                 connection,
                 &mut reader,
                 &final_columns_name,
-                &csv.table_name,
+                schema,
+                &target_table_name,
+                &csv.db_driver,
+                &csv.get_optimized_strategy(),
+                &mut context,
+            )
+            .await
+        }
+        InsertionType::Append => {
+            append_insert(
+                connection,
+                &mut reader,
+                &final_columns_name,
+                schema,
+                &target_table_name,
+                &csv.db_driver,
+                &mut context,
+            )
+            .await
+        }
+        InsertionType::Upsert => {
+            let key_columns: Vec<String> = StringFormatter::get_formated_key_names(
+                &csv.key_columns,
+                &header_names,
+                &final_columns_name,
+                &csv.db_driver,
+            )
+            .map_err(|e| format!("Error: {e}"))?;
+            upsert_insert(
+                connection,
+                &mut reader,
+                &final_columns_name,
+                schema,
+                &target_table_name,
+                &csv.db_driver,
+                &key_columns,
+                &csv.conflict_policy,
+                &mut context,
+            )
+            .await
+        }
+    };
+
+    let cancelled: bool = result.is_err() && context.is_cancelled();
+
+    if let Some(session) = &transactional_connection {
+        let completion: Result<(), String> = match &result {
+            Ok(_) => {
+                commit_import(
+                    session,
+                    &csv.db_driver,
+                    schema,
+                    &csv.table_name,
+                    &target_table_name,
+                )
+                .await
+            }
+            Err(_) => rollback_import(session, &csv.db_driver, schema, &target_table_name).await,
+        };
+        session.disconnect();
+
+        match (&result, completion) {
+            (Ok(_), Err(e)) => return Err(format!("Error: Failed to commit the import: {e}")),
+            (Err(_), Ok(())) if cancelled => {
+                return Err(format!(
+                    "Cancelled: the import into {} was cancelled and rolled back",
+                    table_name
+                ))
+            }
+            (Err(e), Ok(())) => {
+                return Err(format!(
+                    "Error: Failed to insert data, the import was rolled back: {e}"
+                ))
+            }
+            (Err(_), Err(e)) => eprintln!("Error: {e}"),
+            (Ok(_), Ok(())) => {}
+        }
+    }
+
+    // The tables this import created are dropped with their checkpoint. A resumed import, or rows appended
+    // to an existing table, keep the rows committed so far and the checkpoint to resume from
+    if cancelled {
+        if transactional_connection.is_none()
+            && !context.is_resumed()
+            && matches!(mode, InsertionType::Fast | InsertionType::Optimized)
+        {
+            let temporary_table_name: String = get_temporary_table_name(&csv.table_name);
+            match drop_existing_tables(
+                connection,
+                schema,
+                &[&temporary_table_name, &csv.table_name],
                 &csv.db_driver,
             )
             .await
             {
-                Ok(lines) => {
-                    total_lines += u64::from(lines);
-                }
+                Ok(()) => context.remove_checkpoint(),
                 Err(e) => {
-                    return Err(format!("Error: Failed to insert data: {}", e.to_string()));
+                    eprintln!("Error: Failed to drop the tables of the cancelled import: {e}")
                 }
             }
         }
+        return Err(format!(
+            "Cancelled: the import into {} was cancelled",
+            table_name
+        ));
+    }
+
+    match result {
+        Ok(lines) => total_lines += u64::from(lines),
+        Err(e) => return Err(format!("Error: Failed to insert data: {e}")),
+    }
+    context.report_completed(&table_name, total_lines);
+    context.remove_checkpoint();
+
+    if let Err(e) = context.rejects.flush() {
+        eprintln!("Error: {e}");
+    }
+
+    let mut summary: String = format!(
+        "Inserted {total_lines} rows into {table_name} in {:.2} seconds",
+        start.elapsed().as_secs_f64(),
+    );
+    if context.is_resumed() {
+        summary.push_str(&format!(
+            ", resumed after {} already committed rows",
+            context.get_resumed_rows()
+        ));
+    }
+    let rejected_count: u64 = context.rejects.get_rejected_count();
+    if rejected_count > 0 {
+        summary.push_str(&format!(
+            ", {rejected_count} rejected rows written to {}",
+            context.rejects.get_reject_path()
+        ));
+    }
+
+    Ok(ImportReport {
+        table_name,
+        mode,
+        rows_read: context.get_rows_read(),
+        rows_inserted: total_lines,
+        rows_rejected: rejected_count,
+        rows_resumed: context.get_resumed_rows(),
+        bytes_processed: context.get_bytes_processed(),
+        duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        batch_failures: context.get_batch_failures().to_vec(),
+        dialect,
+        ddl: context.get_ddl().to_vec(),
+        warnings: context.get_warnings().to_vec(),
+        schema_drift,
+        reject_path: (rejected_count > 0).then(|| context.rejects.get_reject_path().to_string()),
+        summary,
+    })
+}
+
+/// Show what an import would do: the detected format, the column names, the first rows and the SQL statements.
+/// Only the file is read, the connection is never used
+#[command]
+pub async fn preview_import(
+    csv: InsertConfig,
+    row_count: Option<usize>,
+) -> Result<ImportPreview, String> {
+    const DEFAULT_ROW_COUNT: usize = 20;
+    let row_count: usize = row_count.unwrap_or(DEFAULT_ROW_COUNT);
+    let db_driver: &DatabaseEngine = &csv.db_driver;
+    let schema: Option<&str> = csv.schema.as_deref();
+    let table_name: String = csv.get_qualified_table_name();
+
+    let dialect: CsvDialect = detect_dialect(&csv)?;
+    let mut reader: Reader<FileSection> = dialect.get_reader_builder().from_reader(
+        csv.open_file()
+            .map_err(|e| format!("Error: Failed to open file: {e}"))?,
+    );
+    let header_names: Vec<String> =
+        read_header_names(&mut reader, dialect.has_headers, &csv.column_names)
+            .map_err(|e| format!("Error: {e}"))?;
+    let column_names: Vec<String> =
+        StringFormatter::get_formated_column_names(&header_names, db_driver);
+
+    // The optimized mode infers its types from the whole file, so the whole file is profiled to show its exact DDL
+    let profile_whole_file: bool = csv.mode == InsertionType::Optimized;
+    let mut columns_profile: Vec<ColumnProfile> =
+        vec![ColumnProfile::default(); column_names.len()];
+    let mut records: Vec<StringRecord> = Vec::with_capacity(row_count);
+    for result in reader.records() {
+        // A malformed record would be rejected by the import, it is left out of the preview too
+        let Ok(record) = result else {
+            continue;
+        };
+        if profile_whole_file {
+            observe_record(&mut columns_profile, &record);
+        } else if records.len() >= row_count {
+            break;
+        }
+        if records.len() < row_count {
+            records.push(record);
+        }
+    }
+
+    let column_types: HashMap<&str, ColumnType> =
+        get_column_types(&column_names, &columns_profile, &csv.get_varchar_sizing());
+    let (ddl, insert_table_name): (Vec<String>, String) = match csv.mode {
+        InsertionType::Fast => (
+            vec![build_create_table_sql(
+                db_driver,
+                schema,
+                &csv.table_name,
+                &column_names,
+            )],
+            csv.table_name.clone(),
+        ),
+        InsertionType::Append => (Vec::new(), csv.table_name.clone()),
+        InsertionType::Upsert => {
+            let key_columns: Vec<String> = StringFormatter::get_formated_key_names(
+                &csv.key_columns,
+                &header_names,
+                &column_names,
+                db_driver,
+            )
+            .map_err(|e| format!("Error: {e}"))?;
+            (
+                vec![build_create_table_with_primary_key_sql(
+                    db_driver,
+                    schema,
+                    &csv.table_name,
+                    &column_names,
+                    &key_columns,
+                )],
+                csv.table_name.clone(),
+            )
+        }
+        InsertionType::Optimized => match csv.get_optimized_strategy() {
+            OptimizedStrategy::TemporaryTable => {
+                let temporary_table_name: String = get_temporary_table_name(&csv.table_name);
+                (
+                    vec![
+                        build_create_table_sql(
+                            db_driver,
+                            schema,
+                            &temporary_table_name,
+                            &column_names,
+                        ),
+                        build_create_with_fixed_size_sql(
+                            db_driver,
+                            schema,
+                            &csv.table_name,
+                            &column_types,
+                            &column_names,
+                        ),
+                    ],
+                    temporary_table_name,
+                )
+            }
+            OptimizedStrategy::TwoPass => {
+                // The second pass binds the boolean literals as 1 and 0
+                let boolean_columns: Vec<usize> = get_boolean_columns(&column_names, &column_types);
+                records = records
+                    .into_iter()
+                    .map(|record| normalize_booleans(record, &boolean_columns))
+                    .collect();
+                (
+                    vec![build_create_with_fixed_size_sql(
+                        db_driver,
+                        schema,
+                        &csv.table_name,
+                        &column_types,
+                        &column_names,
+                    )],
+                    csv.table_name.clone(),
+                )
+            }
+            OptimizedStrategy::AlterInPlace => {
+                let mut ddl: Vec<String> = vec![build_create_table_sql(
+                    db_driver,
+                    schema,
+                    &csv.table_name,
+                    &column_names,
+                )];
+                ddl.extend(build_alter_column_types_sql(
+                    schema,
+                    &csv.table_name,
+                    &column_types,
+                    &column_names,
+                ));
+                (ddl, csv.table_name.clone())
+            }
+        },
+    };
+
+    // A fast import into Postgres streams the file with COPY instead of batches of INSERT
+    let insert_sql: String = match (&csv.mode, db_driver) {
+        (InsertionType::Fast, DatabaseEngine::Postgres) => {
+            build_copy_from_stdin_sql(schema, &insert_table_name, &column_names, &dialect)
+        }
+        _ => build_prepared_statement_sql(db_driver, schema, &insert_table_name, &column_names),
     };
 
-    Ok(format!(
-        "Inserted {total_lines} lines into {} tables in {:?} seconds",
-        csv.table_name,
-        start.elapsed(),
-    ))
+    Ok(ImportPreview {
+        insert_sql,
+        table_name,
+        mode: csv.mode,
+        dialect,
+        column_names,
+        rows: records
+            .iter()
+            .map(|record| record.iter().map(String::from).collect())
+            .collect(),
+        ddl,
+    })
+}
+
+/// Compare the csv file with its existing target table: the columns added to the file, missing from it,
+/// renamed, or whose values don't fit in the type of the table column
+#[command]
+pub async fn detect_schema_drift(
+    state: State<'_, Arc<DatabaseState>>,
+    csv: InsertConfig,
+) -> Result<SchemaDrift, String> {
+    let conn_guard = state.0.lock().await;
+
+    if conn_guard.is_none() {
+        return Err("Error: Connection is not established".into());
+    }
+
+    let (drift, _): (SchemaDrift, Vec<ColumnType>) =
+        detect_table_drift(conn_guard.as_ref().unwrap(), &csv).await?;
+    Ok(drift)
+}
+
+/// Compare the columns of the csv file and their types inferred from the whole file with the target table.
+/// Also returns the types of the added columns, an all-empty column being typed TEXT
+async fn detect_table_drift(
+    connection: &Connection,
+    csv: &InsertConfig,
+) -> Result<(SchemaDrift, Vec<ColumnType>), String> {
+    let schema: Option<&str> = csv.schema.as_deref();
+    let table_name: String = csv.get_qualified_table_name();
+    let dialect: CsvDialect = detect_dialect(csv)?;
+    let mut reader: Reader<FileSection> = dialect.get_reader_builder().from_reader(
+        csv.open_file()
+            .map_err(|e| format!("Error: Failed to open file: {e}"))?,
+    );
+    let column_names: Vec<String> = read_column_names(
+        &mut reader,
+        dialect.has_headers,
+        &csv.column_names,
+        &csv.db_driver,
+    )
+    .map_err(|e| format!("Error: {e}"))?;
+
+    let mut columns_profile: Vec<ColumnProfile> =
+        vec![ColumnProfile::default(); column_names.len()];
+    for record in reader.records().flatten() {
+        observe_record(&mut columns_profile, &record);
+    }
+    let sizing: VarcharSizing = csv.get_varchar_sizing();
+    let column_types: HashMap<&str, ColumnType> = column_names
+        .iter()
+        .zip(columns_profile.iter())
+        .filter(|(_, profile)| !profile.is_empty())
+        .map(|(column, profile)| (column.as_str(), profile.column_type(&sizing)))
+        .collect();
+
+    let table_columns: Vec<TableColumn> =
+        get_table_columns(connection, &csv.db_driver, schema, &csv.table_name)
+            .await
+            .map_err(|e| format!("Error: Failed to get the columns of {table_name}: {e}"))?;
+    let drift: SchemaDrift = SchemaDrift::compare(
+        &table_name,
+        &column_names,
+        &column_types,
+        &table_columns,
+        &csv.db_driver,
+    );
+    let added_types: Vec<ColumnType> = drift
+        .added
+        .iter()
+        .map(|column| {
+            column_types
+                .get(column.as_str())
+                .cloned()
+                .unwrap_or(ColumnType::Text)
+        })
+        .collect();
+    Ok((drift, added_types))
+}
+
+/// Separator, quote and header detection of the file, the header setting of the config taking precedence
+fn detect_dialect(csv: &InsertConfig) -> Result<CsvDialect, String> {
+    let file: FileSection = csv
+        .open_file()
+        .map_err(|e| format!("Error: Failed to open file: {e}"))?;
+    let mut dialect: CsvDialect = sniff_dialect_from_reader(file)
+        .map_err(|e| format!("Error: Failed to detect the csv format: {e}"))?;
+    if let Some(has_headers) = csv.has_headers {
+        dialect.has_headers = has_headers;
+    }
+    Ok(dialect)
+}
+
+/// List the import and export runs matching the filter, the most recent first
+#[command]
+pub async fn get_history(
+    app: AppHandle,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<HistoryEntry>, String> {
+    HistoryLog::open(&app)?
+        .list(&filter.unwrap_or_default())
+        .map_err(|e| format!("Error: Failed to read the history: {e}"))
+}
+
+#[command]
+pub async fn clear_history(app: AppHandle) -> Result<bool, String> {
+    HistoryLog::open(&app)?
+        .clear()
+        .map(|_| true)
+        .map_err(|e| format!("Error: Failed to clear the history: {e}"))
+}
+
+#[command]
+pub async fn cancel_job(jobs: State<'_, Arc<JobRegistry>>, job_id: String) -> Result<bool, bool> {
+    if jobs.cancel(&job_id) {
+        return Ok(true);
+    }
+    Err(false)
 }
 
 #[command]
@@ -161,13 +876,10 @@ pub async fn delete_database_config(name: String) -> Result<bool, bool> {
 
 #[command]
 pub async fn get_size_of_file(file_path: String) -> Result<String, bool> {
-    let metadata: Metadata = std::fs::metadata(&file_path).map_err(|_| false)?;
-    if !metadata.is_file() {
-        return Err(false);
-    }
-    if metadata.len() == 0 {
+    let file_size: u64 = get_file_size(&file_path).map_err(|_| false)?;
+    if file_size == 0 {
         return Err(false);
     }
-    let size: f64 = metadata.len() as f64 / 1024.0 / 1024.0;
+    let size: f64 = file_size as f64 / 1024.0 / 1024.0;
     Ok(format!("{size:.2} MB"))
 }
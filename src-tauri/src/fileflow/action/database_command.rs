@@ -1,25 +1,57 @@
 use crate::fileflow::action::actions::DatabaseState;
 use crate::fileflow::database::connection::{Connection, QueryResult};
 use crate::fileflow::database::database_actions::{
-    batch_insert, create_and_copy_final_table, drop_existing_tables, drop_table_if_exists,
-    execute_query, export_table,
+    batch_insert, copy_from_stdin, create_and_copy_final_table, drop_existing_tables,
+    drop_table_if_exists, execute_query, export_table, get_table_columns, get_temporary_table_name,
+    get_unique_keys, get_warning_count,
 };
 use crate::fileflow::database::sql_builder::{
-    build_create_table_sql, build_prepared_statement_sql, build_query_all_tables,
+    build_alter_column_types_sql, build_column_type_sql, build_copy_from_stdin_sql,
+    build_create_table_sql, build_create_table_with_primary_key_sql,
+    build_create_with_fixed_size_sql, build_prepared_statement_sql, build_query_all_schemas,
+    build_query_all_tables, build_upsert_clause_sql, build_value_cast,
 };
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::enumeration::history_operation::HistoryOperation;
+use crate::fileflow::enumeration::optimized_strategy::OptimizedStrategy;
+use crate::fileflow::stuct::batch_limits::BatchLimits;
+use crate::fileflow::stuct::batch_outcome::{BatchFailure, BatchOutcome};
+use crate::fileflow::stuct::column_profile::ColumnProfile;
 use crate::fileflow::stuct::combo_item::ComboItem;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
 use crate::fileflow::stuct::db_config::DbConfig;
 use crate::fileflow::stuct::download_config::DownloadConfig;
-use csv::{Reader, StringRecord};
+use crate::fileflow::stuct::export_report::{ExportReport, TableExport};
+use crate::fileflow::stuct::history_entry::HistoryEntry;
+use crate::fileflow::stuct::history_log::HistoryLog;
+use crate::fileflow::stuct::import_context::ImportContext;
+use crate::fileflow::stuct::job_registry::{JobGuard, JobRegistry};
+use crate::fileflow::stuct::progress::EventProgressListener;
+use crate::fileflow::stuct::string_formater::StringFormatter;
+use crate::fileflow::stuct::table_column::TableColumn;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+use crate::fileflow::utils::constants::EXPORT_PROGRESS_EVENT;
+use crate::fileflow::utils::csv_utils::rewind_reader;
+use csv::{Position, Reader, StringRecord};
 use serde_json::{json, Value};
 use sqlx::Row;
-use std::collections::HashMap;
-use std::fs::File;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek};
 use std::sync::Arc;
 use std::time::Instant;
-use tauri::{command, State};
-use crate::fileflow::stuct::string_formater::StringFormatter;
+use tauri::{command, AppHandle, State};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+/// Batches waiting for an insert worker, with their reading order
+type BatchQueue = Arc<Mutex<Receiver<(u64, Vec<StringRecord>)>>>;
+
+/// Batch sent back by an insert worker with its reading order and its outcome
+type BatchResult = (u64, Vec<StringRecord>, Result<BatchOutcome, String>);
 
 #[command]
 pub async fn connect_to_database(
@@ -79,8 +111,12 @@ pub async fn is_connected(state: State<'_, Arc<DatabaseState>>) -> Result<String
     }
 }
 
+/// List the tables of a schema, the default one of the connection when none is given
 #[command]
-pub async fn get_table_list(state: State<'_, Arc<DatabaseState>>) -> Result<Value, bool> {
+pub async fn get_table_list(
+    state: State<'_, Arc<DatabaseState>>,
+    schema: Option<String>,
+) -> Result<Value, bool> {
     let conn_guard = state.0.lock().await;
 
     if conn_guard.is_none() {
@@ -93,7 +129,10 @@ pub async fn get_table_list(state: State<'_, Arc<DatabaseState>>) -> Result<Valu
     };
 
     let db_config: &DbConfig = connection.get_db_config();
-    let sql: &str = &build_query_all_tables(&db_config.db_driver, &db_config.db_name);
+    let schema: &str = schema
+        .as_deref()
+        .unwrap_or_else(|| db_config.get_default_schema());
+    let sql: &str = &build_query_all_tables(&db_config.db_driver, schema);
 
     let result: QueryResult = connection
         .query_many_with_result(sql)
@@ -132,11 +171,76 @@ pub async fn get_table_list(state: State<'_, Arc<DatabaseState>>) -> Result<Valu
     Ok(json!(vec))
 }
 
+#[command]
+pub async fn get_schema_list(state: State<'_, Arc<DatabaseState>>) -> Result<Value, bool> {
+    let conn_guard = state.0.lock().await;
+
+    let connection: &Connection = match conn_guard.as_ref() {
+        Some(conn) => conn,
+        None => return Err(false),
+    };
+
+    let sql: String = build_query_all_schemas(&connection.get_db_config().db_driver);
+
+    let result: QueryResult = connection
+        .query_many_with_result(&sql)
+        .await
+        .map_err(|_| false)?;
+
+    let schemas: Vec<String> = match result {
+        QueryResult::MySQL(rows) => rows.iter().map(|row| row.get("schema_name")).collect(),
+        QueryResult::Postgres(rows) => rows.iter().map(|row| row.get("schema_name")).collect(),
+        QueryResult::SQLite(rows) => rows.iter().map(|row| row.get("schema_name")).collect(),
+    };
+
+    let vec: Vec<ComboItem> = schemas
+        .into_iter()
+        .map(|schema| ComboItem {
+            value: schema.clone(),
+            label: schema,
+        })
+        .collect();
+
+    Ok(json!(vec))
+}
+
 #[command]
 pub async fn download_table(
+    app: AppHandle,
     config: DownloadConfig,
     state: State<'_, Arc<DatabaseState>>,
-) -> Result<String, String> {
+    jobs: State<'_, Arc<JobRegistry>>,
+) -> Result<ExportReport, String> {
+    let start: Instant = Instant::now();
+    let connection_name: String = state
+        .0
+        .lock()
+        .await
+        .as_ref()
+        .map(|connection| connection.get_db_config().get_connection_name())
+        .unwrap_or_default();
+    let entry: HistoryEntry = HistoryEntry::new(
+        HistoryOperation::Export,
+        &config.location,
+        None,
+        &connection_name,
+        config.table_name_list.clone(),
+        None,
+    );
+
+    let result: Result<ExportReport, String> =
+        export_tables(app.clone(), &config, &state, &jobs).await;
+    HistoryLog::record(&app, &entry.with_export_result(&result, start.elapsed()));
+    result
+}
+
+/// Export each table of the config into its own csv file
+async fn export_tables(
+    app: AppHandle,
+    config: &DownloadConfig,
+    state: &DatabaseState,
+    jobs: &JobRegistry,
+) -> Result<ExportReport, String> {
     let conn_guard = state.0.lock().await;
 
     let start: Instant = Instant::now();
@@ -154,16 +258,41 @@ pub async fn download_table(
         None => return Err("No active database connection.".into()),
     };
 
-    let mut exported_table: u32 = 0;
+    let job: JobGuard = jobs.register(&config.job_id)?;
+    let progress_listener: EventProgressListener =
+        EventProgressListener::new(app, EXPORT_PROGRESS_EVENT);
+    let mut tables: Vec<TableExport> = Vec::with_capacity(config.table_name_list.len());
+    let mut failures: Vec<String> = Vec::new();
     for table_name in config.table_name_list.iter() {
-        if let Err(err) = export_table(connection, &config, table_name).await {
-            println!("{err}");
-            continue;
+        if job.cancel_token.is_cancelled() {
+            break;
         }
-        exported_table += 1;
+        match export_table(
+            connection,
+            config,
+            table_name,
+            &progress_listener,
+            &job.cancel_token,
+        )
+        .await
+        {
+            Ok(table) => tables.push(table),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                failures.push(format!("{table_name}: {err}"));
+            }
+        }
+    }
+
+    let exported_table: usize = tables.len();
+    if job.cancel_token.is_cancelled() {
+        return Err(format!(
+            "Cancelled: exported {exported_table} out of {} tables before the export was cancelled.",
+            config.table_name_list.len()
+        ));
     }
 
-    let response: String = if exported_table == 0 {
+    let summary: String = if exported_table == 0 {
         "No tables were exported.".into()
     } else {
         format!(
@@ -173,169 +302,1179 @@ pub async fn download_table(
         )
     };
 
-    Ok(response)
+    Ok(ExportReport {
+        tables_requested: config.table_name_list.len(),
+        tables,
+        failures,
+        duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        summary,
+    })
 }
 
 /// Fast insert data the csv file into the database table
 pub async fn fast_insert(
     connection: &Connection,
-    reader: &mut Reader<File>,
+    reader: &mut Reader<impl Read>,
     final_columns_name: &[String],
+    schema: Option<&str>,
     final_table_name: &str,
     db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
 ) -> Result<u32, String> {
-    // Drop the table if it exists
-    if let Err(err) = drop_table_if_exists(connection, db_driver, final_table_name).await {
-        eprintln!("Error: {err}");
-        return Err(err);
-    }
+    // A resumed import keeps the table filled by the interrupted one
+    if !context.is_resumed() {
+        // Drop the table if it exists
+        if let Err(err) =
+            drop_table_if_exists(connection, db_driver, schema, final_table_name).await
+        {
+            eprintln!("Error: {err}");
+            return Err(err);
+        }
 
-    let build_create_table_statement: String =
-        build_create_table_sql(db_driver, final_table_name, final_columns_name);
+        let build_create_table_statement: String =
+            build_create_table_sql(db_driver, schema, final_table_name, final_columns_name);
 
-    // Create the table
-    if let Err(err) = execute_query(
-        connection,
-        &build_create_table_statement,
-        "Failed to create table",
-    )
-    .await
-    {
-        eprintln!("Error: {err}");
-        return Err(err);
+        // Create the table
+        if let Err(err) = execute_query(
+            connection,
+            &build_create_table_statement,
+            "Failed to create table",
+        )
+        .await
+        {
+            eprintln!("Error: {err}");
+            return Err(err);
+        }
+        context.record_ddl(&build_create_table_statement);
     }
 
     const MAX_BATCH_SIZE: usize = 5_000;
     let mut line_count: u32 = 0;
-    let mut batch: Vec<String> = Vec::with_capacity(MAX_BATCH_SIZE);
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
 
     // Prepare the insert query
     let insert_query_base: &str =
-        &build_prepared_statement_sql(db_driver, final_table_name, &final_columns_name);
+        &build_prepared_statement_sql(db_driver, schema, final_table_name, final_columns_name);
+
+    // SQLite allows a single writer and a transaction lives on a single session, both insert one batch at a time
+    let workers: usize = context.get_workers();
+    if workers > 1 && *db_driver != DatabaseEngine::SQLite && !connection.is_transactional() {
+        return parallel_insert(
+            connection,
+            reader,
+            insert_query_base,
+            final_table_name,
+            workers,
+            context,
+        )
+        .await;
+    }
 
     for result in reader.records() {
-        let values: String = match result {
-            Ok(record) => StringFormatter::escaped_record(record),
-            Err(_) => continue,
+        let Some(record) = context.accept_record(result)? else {
+            continue;
         };
 
-        batch.push(format!("({values})"));
+        batch.push(record);
 
         if batch.len() >= MAX_BATCH_SIZE {
-            line_count += insert_batch(connection, insert_query_base, &batch).await;
+            context.check_cancelled()?;
+            line_count +=
+                insert_batch(connection, insert_query_base, &batch, &[], "", context).await?;
+            context.report_batch(final_table_name, &batch, line_count);
+            context.save_checkpoint(&batch, line_count);
             batch.clear();
         }
     }
 
     // Insert the remaining records if any
-    line_count += insert_batch(connection, insert_query_base, &batch).await;
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        line_count += insert_batch(connection, insert_query_base, &batch, &[], "", context).await?;
+        context.report_batch(final_table_name, &batch, line_count);
+        context.save_checkpoint(&batch, line_count);
+    }
+
+    Ok(line_count)
+}
+
+/// Insert the records of the csv file with several workers, each one inserting a batch on its own pool connection.
+/// The records are parsed here and sent to the workers through a bounded channel, the outcome of each batch
+/// is then applied in reading order so that rejects, progress and checkpoints stay consistent
+pub async fn parallel_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    insert_query_base: &str,
+    final_table_name: &str,
+    workers: usize,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    const MAX_BATCH_SIZE: usize = 5_000;
+
+    let (batch_sender, batch_receiver) = mpsc::channel::<(u64, Vec<StringRecord>)>(workers);
+    let batch_receiver: BatchQueue = Arc::new(Mutex::new(batch_receiver));
+    let (outcome_sender, mut outcome_receiver) = mpsc::unbounded_channel::<BatchResult>();
+
+    // Dropping the join set aborts the workers when the import stops early
+    let mut tasks: JoinSet<()> = JoinSet::new();
+    for _ in 0..workers {
+        let connection: Connection = connection.clone();
+        let insert_query_base: String = insert_query_base.to_string();
+        let batch_receiver: BatchQueue = Arc::clone(&batch_receiver);
+        let outcome_sender: UnboundedSender<BatchResult> = outcome_sender.clone();
+        tasks.spawn(async move {
+            loop {
+                let next: Option<(u64, Vec<StringRecord>)> =
+                    batch_receiver.lock().await.recv().await;
+                let Some((sequence, batch)) = next else {
+                    break;
+                };
+                let outcome: Result<BatchOutcome, String> =
+                    try_insert_batch(&connection, &insert_query_base, &batch, &[], "").await;
+                if outcome_sender.send((sequence, batch, outcome)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(outcome_sender);
+
+    let mut pending: BTreeMap<u64, (Vec<StringRecord>, BatchOutcome)> = BTreeMap::new();
+    let mut next_sequence: u64 = 0;
+    let mut sent_count: u64 = 0;
+    let mut line_count: u32 = 0;
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
+
+    for result in reader.records() {
+        let Some(record) = context.accept_record(result)? else {
+            continue;
+        };
+
+        batch.push(record);
+
+        if batch.len() >= MAX_BATCH_SIZE {
+            context.check_cancelled()?;
+            let full_batch: Vec<StringRecord> =
+                std::mem::replace(&mut batch, Vec::with_capacity(MAX_BATCH_SIZE));
+            batch_sender
+                .send((sent_count, full_batch))
+                .await
+                .map_err(|_| "Insert workers stopped unexpectedly")?;
+            sent_count += 1;
+
+            // Apply the batches the workers already finished
+            while let Ok((sequence, batch, outcome)) = outcome_receiver.try_recv() {
+                pending.insert(sequence, (batch, outcome?));
+            }
+            apply_batch_outcomes(
+                &mut pending,
+                &mut next_sequence,
+                &mut line_count,
+                final_table_name,
+                context,
+            )?;
+        }
+    }
+
+    // Send the remaining records if any
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        batch_sender
+            .send((sent_count, batch))
+            .await
+            .map_err(|_| "Insert workers stopped unexpectedly")?;
+        sent_count += 1;
+    }
+    drop(batch_sender);
+
+    // The channel is closed once every worker is done
+    while let Some((sequence, batch, outcome)) = outcome_receiver.recv().await {
+        pending.insert(sequence, (batch, outcome?));
+        apply_batch_outcomes(
+            &mut pending,
+            &mut next_sequence,
+            &mut line_count,
+            final_table_name,
+            context,
+        )?;
+    }
+    while let Some(joined) = tasks.join_next().await {
+        joined.map_err(|err| format!("Insert worker failed: {err}"))?;
+    }
+
+    if next_sequence != sent_count {
+        return Err(format!(
+            "Only {next_sequence} out of {sent_count} batches were inserted"
+        ));
+    }
+
+    Ok(line_count)
+}
+
+/// Apply the outcomes of the finished batches that follow the last applied one, a checkpoint never skips a batch still running
+fn apply_batch_outcomes(
+    pending: &mut BTreeMap<u64, (Vec<StringRecord>, BatchOutcome)>,
+    next_sequence: &mut u64,
+    line_count: &mut u32,
+    final_table_name: &str,
+    context: &mut ImportContext,
+) -> Result<(), String> {
+    while let Some((batch, outcome)) = pending.remove(next_sequence) {
+        for (record, err) in outcome.rejected.iter() {
+            context.rejects.reject_record(record, err)?;
+        }
+        for failure in outcome.failures {
+            context.record_batch_failure(failure);
+        }
+        *line_count += outcome.inserted_count;
+        context.report_batch(final_table_name, &batch, *line_count);
+        context.save_checkpoint(&batch, *line_count);
+        *next_sequence += 1;
+    }
+    Ok(())
+}
+
+/// Load the csv data into a new Postgres table through COPY FROM STDIN, much faster than batched INSERT
+pub async fn copy_insert(
+    connection: &Connection,
+    source: impl Read,
+    dialect: &CsvDialect,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    let db_driver: &DatabaseEngine = &DatabaseEngine::Postgres;
+
+    drop_table_if_exists(connection, db_driver, schema, final_table_name).await?;
+
+    let create_table_statement: String =
+        build_create_table_sql(db_driver, schema, final_table_name, final_columns_name);
+    execute_query(
+        connection,
+        &create_table_statement,
+        "Failed to create table",
+    )
+    .await?;
+
+    let copy_statement: String =
+        build_copy_from_stdin_sql(schema, final_table_name, final_columns_name, dialect);
+    let header_lines: u64 = u64::from(dialect.has_headers);
+    let mut bytes_sent: u64 = 0;
+    let mut lines_sent: u64 = 0;
+    let line_count: u64 = copy_from_stdin(connection, &copy_statement, source, |chunk: &[u8]| {
+        // A cancelled import aborts the COPY, none of the rows sent are kept
+        context.check_cancelled()?;
+        bytes_sent += chunk.len() as u64;
+        lines_sent += chunk.iter().filter(|byte| **byte == b'\n').count() as u64;
+        // The rows are counted by line terminator until COPY returns, a quoted field may span several lines
+        context.report(
+            final_table_name,
+            bytes_sent,
+            lines_sent.saturating_sub(header_lines),
+        );
+        Ok(())
+    })
+    .await?;
+    context.record_ddl(&create_table_statement);
+    context.add_rows_read(line_count);
+
+    Ok(u32::try_from(line_count).unwrap_or(u32::MAX))
+}
+
+/// Load the csv data into a new MySQL or MariaDB table with statements as large as the `max_allowed_packet` of the server
+/// and the bind parameters limit allow, instead of batches of a fixed number of rows. The statements run on a session
+/// of their own whose unique and foreign key checks are disabled, the warnings raised by the server are counted
+pub async fn bulk_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    if !matches!(db_driver, DatabaseEngine::MySQL | DatabaseEngine::MariaDB) {
+        return Err("The bulk load is only available for MySQL and MariaDB".into());
+    }
+
+    // The warnings count is only readable from the session that ran the statements, a transaction already has its own
+    let dedicated_session: Option<Connection> = if connection.is_transactional() {
+        None
+    } else {
+        let session: Connection = Connection::connect_session(connection.get_db_config())
+            .await
+            .map_err(|err| format!("Failed to open the bulk load session: {err}"))?;
+        if let Err(err) = execute_query(
+            &session,
+            "SET SESSION unique_checks = 0, foreign_key_checks = 0",
+            "Failed to disable the checks of the bulk load session",
+        )
+        .await
+        {
+            eprintln!("Error: {err}");
+            context.add_warning(err);
+        }
+        Some(session)
+    };
+    let session: &Connection = dedicated_session.as_ref().unwrap_or(connection);
 
+    let result: Result<(u32, u64), String> = bulk_load_records(
+        session,
+        reader,
+        final_columns_name,
+        schema,
+        final_table_name,
+        db_driver,
+        context,
+    )
+    .await;
+    if let Some(session) = dedicated_session {
+        session.disconnect();
+    }
+
+    let (line_count, warnings): (u32, u64) = result?;
+    if warnings > 0 {
+        context.add_warning(format!(
+            "The server raised {warnings} warnings during the bulk load"
+        ));
+    }
     Ok(line_count)
 }
 
+/// Create the table and insert the records with the largest statements the session accepts, returns the inserted lines and the warnings count
+async fn bulk_load_records(
+    session: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<(u32, u64), String> {
+    // A resumed import keeps the table filled by the interrupted one
+    if !context.is_resumed() {
+        drop_table_if_exists(session, db_driver, schema, final_table_name).await?;
+
+        let create_table_statement: String =
+            build_create_table_sql(db_driver, schema, final_table_name, final_columns_name);
+        execute_query(session, &create_table_statement, "Failed to create table").await?;
+        context.record_ddl(&create_table_statement);
+    }
+
+    let insert_query_base: &str =
+        &build_prepared_statement_sql(db_driver, schema, final_table_name, final_columns_name);
+    let limits: BatchLimits = *session.get_batch_limits();
+    let mut line_count: u32 = 0;
+    let mut warnings: u64 = 0;
+    let mut batch: Vec<StringRecord> = Vec::new();
+    let mut parameters: usize = 0;
+    let mut bytes: usize = 0;
+
+    for result in reader.records() {
+        let Some(record) = context.accept_record(result)? else {
+            continue;
+        };
+        let record_parameters: usize = record.len().max(1);
+        let record_bytes: usize = BatchLimits::get_record_size(&record);
+
+        // Each batch is sent as a single statement, filled until the next record would not fit
+        if !batch.is_empty() && limits.exceeds(parameters + record_parameters, bytes + record_bytes)
+        {
+            context.check_cancelled()?;
+            line_count +=
+                insert_batch(session, insert_query_base, &batch, &[], "", context).await?;
+            warnings += count_warnings(session).await;
+            context.report_batch(final_table_name, &batch, line_count);
+            context.save_checkpoint(&batch, line_count);
+            batch.clear();
+            parameters = 0;
+            bytes = 0;
+        }
+        batch.push(record);
+        parameters += record_parameters;
+        bytes += record_bytes;
+    }
+
+    // Insert the remaining records if any
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        line_count += insert_batch(session, insert_query_base, &batch, &[], "", context).await?;
+        warnings += count_warnings(session).await;
+        context.report_batch(final_table_name, &batch, line_count);
+        context.save_checkpoint(&batch, line_count);
+    }
+
+    Ok((line_count, warnings))
+}
+
+/// Warnings raised by the last statement of the session, a failure to read them does not stop the import
+async fn count_warnings(session: &Connection) -> u64 {
+    get_warning_count(session).await.unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        0
+    })
+}
+
 /// Insert data into the database using the optimized table creation and insertion method
+#[allow(clippy::too_many_arguments)]
 pub async fn optimized_insert(
     connection: &Connection,
-    reader: &mut Reader<File>,
+    reader: &mut Reader<impl Read + Seek>,
     final_columns_name: &[String],
+    schema: Option<&str>,
     final_table_name: &str,
     db_driver: &DatabaseEngine,
+    strategy: &OptimizedStrategy,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    if !strategy.is_supported_by(db_driver) {
+        return Err(format!(
+            "Error: The {strategy:?} strategy is not supported by {db_driver:?}"
+        ));
+    }
+
+    match strategy {
+        OptimizedStrategy::TemporaryTable => {
+            temporary_table_insert(
+                connection,
+                reader,
+                final_columns_name,
+                schema,
+                final_table_name,
+                db_driver,
+                context,
+            )
+            .await
+        }
+        OptimizedStrategy::TwoPass => {
+            two_pass_insert(
+                connection,
+                reader,
+                final_columns_name,
+                schema,
+                final_table_name,
+                db_driver,
+                context,
+            )
+            .await
+        }
+        OptimizedStrategy::AlterInPlace => {
+            alter_in_place_insert(
+                connection,
+                reader,
+                final_columns_name,
+                schema,
+                final_table_name,
+                db_driver,
+                context,
+            )
+            .await
+        }
+    }
+}
+
+/// Load the file in a temporary text table, then copy it into the typed final table
+async fn temporary_table_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
 ) -> Result<u32, String> {
     // Drop existing tables
-    let temporary_table_name: String = format!("{final_table_name}_temporary");
+    let temporary_table_name: String = get_temporary_table_name(final_table_name);
     drop_existing_tables(
         connection,
+        schema,
         &[&temporary_table_name, final_table_name],
         db_driver,
     )
-    .await
-    .expect("Failed to drop existing tables");
+    .await?;
 
     // Create the temporary table
     let create_temp_table_query: String =
-        build_create_table_sql(db_driver, &temporary_table_name, final_columns_name);
+        build_create_table_sql(db_driver, schema, &temporary_table_name, final_columns_name);
 
     execute_query(
         connection,
         &create_temp_table_query,
         "Failed to create temporary table",
     )
-    .await
-    .expect("Failed to create temporary table query");
+    .await?;
 
-    // Initialize variables
+    let (line_count, columns_profile) = load_text_table(
+        connection,
+        reader,
+        final_columns_name,
+        schema,
+        &temporary_table_name,
+        final_table_name,
+        db_driver,
+        context,
+    )
+    .await?;
+
+    // Create final table and copy data
+    let column_types: HashMap<&str, ColumnType> = get_column_types(
+        final_columns_name,
+        &columns_profile,
+        context.get_varchar_sizing(),
+    );
+    let create_final_table_query: String = create_and_copy_final_table(
+        connection,
+        db_driver,
+        schema,
+        final_table_name,
+        &temporary_table_name,
+        &column_types,
+        final_columns_name,
+    )
+    .await?;
+    context.record_ddl(&create_final_table_query);
+
+    drop_table_if_exists(connection, db_driver, schema, &temporary_table_name).await?; // Drop the temporary table
+
+    Ok(line_count)
+}
+
+/// Read the file a first time to infer the type of each column, then a second time to insert it into the typed final table
+async fn two_pass_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read + Seek>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
     const MAX_BATCH_SIZE: usize = 5_000;
-    let insert_query_base: String =
-        build_prepared_statement_sql(db_driver, &temporary_table_name, &final_columns_name);
 
-    let mut columns_size_map: HashMap<&str, usize> = final_columns_name
+    // First pass: profile the columns, the malformed records are rejected once here and skipped by the second pass
+    let mut columns_profile: Vec<ColumnProfile> =
+        vec![ColumnProfile::default(); final_columns_name.len()];
+    for (index, result) in reader.records().enumerate() {
+        if let Some(record) = context.accept_record(result)? {
+            observe_record(&mut columns_profile, &record);
+        }
+
+        if index % MAX_BATCH_SIZE == 0 {
+            context.check_cancelled()?;
+        }
+    }
+
+    // Create the typed final table
+    let column_types: HashMap<&str, ColumnType> = get_column_types(
+        final_columns_name,
+        &columns_profile,
+        context.get_varchar_sizing(),
+    );
+    drop_existing_tables(connection, schema, &[final_table_name], db_driver).await?;
+    let create_final_table_query: String = build_create_with_fixed_size_sql(
+        db_driver,
+        schema,
+        final_table_name,
+        &column_types,
+        final_columns_name,
+    );
+    execute_query(
+        connection,
+        &create_final_table_query,
+        "Failed to create final table",
+    )
+    .await?;
+    context.record_ddl(&create_final_table_query);
+
+    // Second pass: insert the records, converting the text values to the type of their column
+    rewind_reader(reader)?;
+    let insert_query_base: String =
+        build_prepared_statement_sql(db_driver, schema, final_table_name, final_columns_name);
+    let value_casts: Vec<Option<String>> = final_columns_name
         .iter()
-        .map(|col| (col.as_str(), 0))
-        .collect(); // Initialize column size map for each column -> 0 (id,size)
+        .map(|column| {
+            column_types
+                .get(column.as_str())
+                .filter(|column_type| !column_type.is_textual())
+                .map(|column_type| build_column_type_sql(db_driver, column_type))
+        })
+        .collect();
+    let boolean_columns: Vec<usize> = get_boolean_columns(final_columns_name, &column_types);
+
     let mut line_count: u32 = 0;
-    let mut batch: Vec<String> = Vec::with_capacity(MAX_BATCH_SIZE);
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
 
     for result in reader.records() {
         let record: StringRecord = match result {
             Ok(record) => record,
             Err(_) => continue,
         };
-
-        let mut values: Vec<String> = Vec::with_capacity(record.len());
-
-        for (i, value) in record.iter().enumerate() {
-            let sanitized_value: String = StringFormatter::sanitize_value(value);
-            let max_length: &mut usize = columns_size_map
-                .get_mut(final_columns_name[i].as_str())
-                .ok_or("Column name mismatch")
-                .expect("Column name mismatch");
-            *max_length = (*max_length).max(sanitized_value.len() + 1);
-            values.push(format!("'{sanitized_value}'"));
-        }
-
-        batch.push(format!("({})", values.join(", ")));
+        batch.push(normalize_booleans(record, &boolean_columns));
 
         if batch.len() >= MAX_BATCH_SIZE {
-            line_count += insert_batch(connection, &insert_query_base, &batch).await;
+            context.check_cancelled()?;
+            line_count += insert_batch(
+                connection,
+                &insert_query_base,
+                &batch,
+                &value_casts,
+                "",
+                context,
+            )
+            .await?;
+            context.report_batch(final_table_name, &batch, line_count);
+            context.save_checkpoint(&batch, line_count);
             batch.clear();
         }
     }
 
     // Insert remaining records
+    context.check_cancelled()?;
     if !batch.is_empty() {
-        line_count += insert_batch(connection, &insert_query_base, &batch).await;
+        line_count += insert_batch(
+            connection,
+            &insert_query_base,
+            &batch,
+            &value_casts,
+            "",
+            context,
+        )
+        .await?;
+        context.report_batch(final_table_name, &batch, line_count);
+        context.save_checkpoint(&batch, line_count);
     }
 
-    // Create final table and copy data
-    create_and_copy_final_table(
+    Ok(line_count)
+}
+
+/// Load the file in the final table as text, then convert its columns to their inferred type in place (Postgres only)
+async fn alter_in_place_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    drop_existing_tables(connection, schema, &[final_table_name], db_driver).await?;
+
+    let create_text_table_query: String =
+        build_create_table_sql(db_driver, schema, final_table_name, final_columns_name);
+    execute_query(
         connection,
-        db_driver,
-        final_table_name,
-        &temporary_table_name,
-        &columns_size_map,
+        &create_text_table_query,
+        "Failed to create final table",
+    )
+    .await?;
+    context.record_ddl(&create_text_table_query);
+
+    let (line_count, columns_profile) = load_text_table(
+        connection,
+        reader,
         final_columns_name,
+        schema,
+        final_table_name,
+        final_table_name,
+        db_driver,
+        context,
     )
     .await?;
 
-    drop_table_if_exists(connection, db_driver, &temporary_table_name).await?; // Drop the temporary table
+    let column_types: HashMap<&str, ColumnType> = get_column_types(
+        final_columns_name,
+        &columns_profile,
+        context.get_varchar_sizing(),
+    );
+    if let Some(alter_query) =
+        build_alter_column_types_sql(schema, final_table_name, &column_types, final_columns_name)
+    {
+        execute_query(
+            connection,
+            &alter_query,
+            "Failed to convert the columns of the final table",
+        )
+        .await?;
+        context.record_ddl(&alter_query);
+    }
 
     Ok(line_count)
 }
 
+/// Insert the records of the file into a table of text columns, profiling each column on the way
+#[allow(clippy::too_many_arguments)]
+async fn load_text_table(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    text_table_name: &str,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<(u32, Vec<ColumnProfile>), String> {
+    // Initialize variables
+    const MAX_BATCH_SIZE: usize = 5_000;
+    let insert_query_base: String =
+        build_prepared_statement_sql(db_driver, schema, text_table_name, final_columns_name);
+
+    let mut columns_profile: Vec<ColumnProfile> =
+        vec![ColumnProfile::default(); final_columns_name.len()];
+    let mut line_count: u32 = 0;
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
+
+    for result in reader.records() {
+        let Some(record) = context.accept_record(result)? else {
+            continue;
+        };
+
+        observe_record(&mut columns_profile, &record);
+        batch.push(record);
+
+        if batch.len() >= MAX_BATCH_SIZE {
+            context.check_cancelled()?;
+            line_count +=
+                insert_batch(connection, &insert_query_base, &batch, &[], "", context).await?;
+            context.report_batch(final_table_name, &batch, line_count);
+            context.save_checkpoint(&batch, line_count);
+            batch.clear();
+        }
+    }
+
+    // Insert remaining records
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        line_count +=
+            insert_batch(connection, &insert_query_base, &batch, &[], "", context).await?;
+        context.report_batch(final_table_name, &batch, line_count);
+        context.save_checkpoint(&batch, line_count);
+    }
+
+    Ok((line_count, columns_profile))
+}
+
+/// Observe each value of a record in the profile of its column
+pub fn observe_record(columns_profile: &mut [ColumnProfile], record: &StringRecord) {
+    for (profile, value) in columns_profile.iter_mut().zip(record.iter()) {
+        profile.observe(value);
+    }
+}
+
+/// Type of each column inferred from its profile
+pub fn get_column_types<'a>(
+    final_columns_name: &'a [String],
+    columns_profile: &[ColumnProfile],
+    varchar_sizing: &VarcharSizing,
+) -> HashMap<&'a str, ColumnType> {
+    final_columns_name
+        .iter()
+        .zip(columns_profile.iter())
+        .map(|(column, profile)| (column.as_str(), profile.column_type(varchar_sizing)))
+        .collect()
+}
+
+/// Index of the columns inferred as boolean
+pub fn get_boolean_columns(
+    final_columns_name: &[String],
+    column_types: &HashMap<&str, ColumnType>,
+) -> Vec<usize> {
+    final_columns_name
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| column_types.get(column.as_str()) == Some(&ColumnType::Boolean))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Write the boolean literals of the given columns as 1 and 0, the spelling every engine stores in a boolean column
+pub fn normalize_booleans(record: StringRecord, boolean_columns: &[usize]) -> StringRecord {
+    if boolean_columns.is_empty() {
+        return record;
+    }
+
+    let mut normalized: StringRecord = record
+        .iter()
+        .enumerate()
+        .map(|(index, value)| match ColumnProfile::parse_boolean(value) {
+            Some(true) if boolean_columns.contains(&index) => "1",
+            Some(false) if boolean_columns.contains(&index) => "0",
+            _ => value,
+        })
+        .collect();
+    normalized.set_position(record.position().cloned());
+    normalized
+}
+
+/// Append the data of the csv file into an existing table, keeping its rows, indexes and grants
+pub async fn append_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    let table_columns: Vec<TableColumn> =
+        get_table_columns(connection, db_driver, schema, final_table_name).await?;
+
+    if table_columns.is_empty() {
+        return Err(format!("Table '{final_table_name}' does not exist"));
+    }
+
+    let target_columns: Vec<String> = match_table_columns(
+        final_columns_name,
+        &table_columns,
+        final_table_name,
+        db_driver,
+    )?;
+    let value_casts: Vec<Option<String>> =
+        get_value_casts(db_driver, &target_columns, &table_columns);
+
+    const MAX_BATCH_SIZE: usize = 5_000;
+    let mut line_count: u32 = 0;
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
+
+    let insert_query_base: &str =
+        &build_prepared_statement_sql(db_driver, schema, final_table_name, &target_columns);
+
+    for result in reader.records() {
+        let Some(record) = context.accept_record(result)? else {
+            continue;
+        };
+
+        batch.push(record);
+
+        if batch.len() >= MAX_BATCH_SIZE {
+            context.check_cancelled()?;
+            line_count += insert_batch(
+                connection,
+                insert_query_base,
+                &batch,
+                &value_casts,
+                "",
+                context,
+            )
+            .await?;
+            context.report_batch(final_table_name, &batch, line_count);
+            context.save_checkpoint(&batch, line_count);
+            batch.clear();
+        }
+    }
+
+    // Insert the remaining records if any
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        line_count += insert_batch(
+            connection,
+            insert_query_base,
+            &batch,
+            &value_casts,
+            "",
+            context,
+        )
+        .await?;
+        context.report_batch(final_table_name, &batch, line_count);
+        context.save_checkpoint(&batch, line_count);
+    }
+
+    Ok(line_count)
+}
+
+/// Insert or update the data of the csv file into a table, rows sharing the same key columns are merged
+#[allow(clippy::too_many_arguments)]
+pub async fn upsert_insert(
+    connection: &Connection,
+    reader: &mut Reader<impl Read>,
+    final_columns_name: &[String],
+    schema: Option<&str>,
+    final_table_name: &str,
+    db_driver: &DatabaseEngine,
+    key_columns: &[String],
+    conflict_policy: &ConflictPolicy,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    if key_columns.is_empty() {
+        return Err("At least one key column is required for an upsert".into());
+    }
+
+    // Position of each key column in the csv records, the keys being formatted column names
+    let mut key_indexes: Vec<usize> = Vec::with_capacity(key_columns.len());
+    for key in key_columns.iter() {
+        let index: usize = final_columns_name
+            .iter()
+            .position(|column| column == key)
+            .ok_or_else(|| format!("Key column '{key}' does not exist in the csv file"))?;
+        key_indexes.push(index);
+    }
+
+    let table_columns: Vec<TableColumn> =
+        get_table_columns(connection, db_driver, schema, final_table_name).await?;
+
+    let target_columns: Vec<String> = if table_columns.is_empty() {
+        let target_keys: Vec<String> = key_indexes
+            .iter()
+            .map(|i| final_columns_name[*i].clone())
+            .collect();
+        let create_table_statement: String = build_create_table_with_primary_key_sql(
+            db_driver,
+            schema,
+            final_table_name,
+            final_columns_name,
+            &target_keys,
+        );
+        execute_query(
+            connection,
+            &create_table_statement,
+            "Failed to create table",
+        )
+        .await?;
+        context.record_ddl(&create_table_statement);
+        final_columns_name.to_vec()
+    } else {
+        match_table_columns(
+            final_columns_name,
+            &table_columns,
+            final_table_name,
+            db_driver,
+        )?
+    };
+    let value_casts: Vec<Option<String>> =
+        get_value_casts(db_driver, &target_columns, &table_columns);
+
+    let target_keys: Vec<String> = key_indexes
+        .iter()
+        .map(|i| target_columns[*i].clone())
+        .collect();
+
+    // The conflict clause only applies to a unique index or primary key made of exactly the key columns
+    if !table_columns.is_empty() {
+        let unique_keys: Vec<Vec<String>> =
+            get_unique_keys(connection, db_driver, schema, final_table_name).await?;
+        let is_unique: bool = unique_keys.iter().any(|columns| {
+            columns.len() == target_keys.len()
+                && target_keys.iter().all(|key| columns.contains(key))
+        });
+        if !is_unique {
+            return Err(format!(
+                "Table '{final_table_name}' has no unique index or primary key on the key columns {}",
+                target_keys.join(", ")
+            ));
+        }
+    }
+    let conflict_clause: String =
+        build_upsert_clause_sql(db_driver, &target_columns, &target_keys, conflict_policy);
+    let insert_query_base: &str =
+        &build_prepared_statement_sql(db_driver, schema, final_table_name, &target_columns);
+
+    const MAX_BATCH_SIZE: usize = 5_000;
+    let mut line_count: u32 = 0;
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(MAX_BATCH_SIZE);
+    let mut batch_keys: HashMap<Vec<String>, usize> = HashMap::with_capacity(MAX_BATCH_SIZE);
+    // Merged duplicates leave the batch out of file order, its last record isn't always the furthest one read
+    let mut furthest_read: Position = Position::new();
+
+    for result in reader.records() {
+        let Some(record) = context.accept_record(result)? else {
+            continue;
+        };
+        if let Some(position) = record.position() {
+            furthest_read = position.clone();
+        }
+
+        let key: Vec<String> = key_indexes
+            .iter()
+            .map(|i| record.get(*i).unwrap_or_default().to_string())
+            .collect();
+
+        // A statement can't touch the same key twice, so duplicates are merged inside the batch
+        match (batch_keys.get(&key), conflict_policy) {
+            (Some(index), ConflictPolicy::Update) => batch[*index] = record,
+            (Some(_), ConflictPolicy::Ignore) => {}
+            _ => {
+                batch_keys.insert(key, batch.len());
+                batch.push(record);
+            }
+        }
+
+        if batch.len() >= MAX_BATCH_SIZE {
+            context.check_cancelled()?;
+            line_count += insert_batch(
+                connection,
+                insert_query_base,
+                &batch,
+                &value_casts,
+                &conflict_clause,
+                context,
+            )
+            .await?;
+            context.report(
+                final_table_name,
+                furthest_read.byte(),
+                u64::from(line_count),
+            );
+            context.save_checkpoint_at(&furthest_read, line_count);
+            batch.clear();
+            batch_keys.clear();
+        }
+    }
+
+    // Insert the remaining records if any
+    context.check_cancelled()?;
+    if !batch.is_empty() {
+        line_count += insert_batch(
+            connection,
+            insert_query_base,
+            &batch,
+            &value_casts,
+            &conflict_clause,
+            context,
+        )
+        .await?;
+        context.report(
+            final_table_name,
+            furthest_read.byte(),
+            u64::from(line_count),
+        );
+        context.save_checkpoint_at(&furthest_read, line_count);
+    }
+
+    Ok(line_count)
+}
+
+/// Match the csv columns with the columns of an existing table and return the table column names in csv order
+pub fn match_table_columns(
+    csv_columns: &[String],
+    table_columns: &[TableColumn],
+    table_name: &str,
+    db_driver: &DatabaseEngine,
+) -> Result<Vec<String>, String> {
+    let mut target_columns: Vec<String> = Vec::with_capacity(csv_columns.len());
+
+    // Table columns are formatted and deduplicated like the csv headers, so that colliding names keep their suffix
+    let table_names: Vec<String> = StringFormatter::get_formated_column_names(
+        &table_columns
+            .iter()
+            .map(|column| column.name.clone())
+            .collect::<Vec<String>>(),
+        db_driver,
+    );
+    for csv_column in csv_columns.iter() {
+        let index: usize = table_names
+            .iter()
+            .position(|name| name == csv_column)
+            .ok_or_else(|| {
+                format!("Column '{csv_column}' does not exist in table '{table_name}'")
+            })?;
+        target_columns.push(table_columns[index].name.clone());
+    }
+
+    // Every column that can't be left empty must be provided by the csv file
+    if let Some(missing) = table_columns
+        .iter()
+        .find(|column| column.is_required() && !target_columns.contains(&column.name))
+    {
+        return Err(format!(
+            "Required column '{}' of table '{table_name}' is missing from the csv file",
+            missing.name
+        ));
+    }
+
+    Ok(target_columns)
+}
+
+/// Get the cast to apply to each bound value so that it fits the type of the matching table column
+fn get_value_casts(
+    db_driver: &DatabaseEngine,
+    target_columns: &[String],
+    table_columns: &[TableColumn],
+) -> Vec<Option<String>> {
+    target_columns
+        .iter()
+        .map(|name| {
+            table_columns
+                .iter()
+                .find(|column| column.name == *name)
+                .and_then(|column| build_value_cast(db_driver, &column.data_type))
+        })
+        .collect()
+}
+
 /// Insert a batch of records into the database
-async fn insert_batch(connection: &Connection, insert_query_base: &str, batch: &[String]) -> u32 {
-    match batch_insert(
+async fn insert_batch(
+    connection: &Connection,
+    insert_query_base: &str,
+    batch: &[StringRecord],
+    value_casts: &[Option<String>],
+    conflict_clause: &str,
+    context: &mut ImportContext,
+) -> Result<u32, String> {
+    let outcome: BatchOutcome = try_insert_batch(
         connection,
         insert_query_base,
         batch,
-        "Failed to insert batch data",
+        value_casts,
+        conflict_clause,
     )
-    .await
-    {
-        Ok(_) => u32::try_from(batch.len()).unwrap_or(5_000),
-        Err(err) => {
-            eprintln!("Error inserting batch: {err}");
-            0
+    .await?;
+
+    for (record, err) in outcome.rejected.iter() {
+        context.rejects.reject_record(record, err)?;
+    }
+    for failure in outcome.failures {
+        context.record_batch_failure(failure);
+    }
+    Ok(outcome.inserted_count)
+}
+
+/// Insert a batch of records, a failed batch is retried row by row and the refused rows are returned instead of written
+async fn try_insert_batch(
+    connection: &Connection,
+    insert_query_base: &str,
+    batch: &[StringRecord],
+    value_casts: &[Option<String>],
+    conflict_clause: &str,
+) -> Result<BatchOutcome, String> {
+    let mut outcome: BatchOutcome = BatchOutcome::default();
+
+    // Each statement is retried on its own, so that the rows of the statements already inserted are not inserted twice
+    for rows in connection.get_batch_limits().split(batch) {
+        match batch_insert(
+            connection,
+            insert_query_base,
+            rows,
+            value_casts,
+            conflict_clause,
+            "Failed to insert batch data",
+        )
+        .await
+        {
+            Ok(_) => outcome.inserted_count += u32::try_from(rows.len()).unwrap_or(u32::MAX),
+            // A transactional import must stop at the first failure so that everything is rolled back
+            Err(err) if connection.is_transactional() => return Err(err),
+            Err(err) => {
+                eprintln!("Error inserting batch, retrying row by row: {err}");
+                outcome.failures.push(BatchFailure::new(rows, &err));
+
+                // Retry the rows one at a time so that only the faulty ones are rejected
+                for record in rows.iter() {
+                    match batch_insert(
+                        connection,
+                        insert_query_base,
+                        std::slice::from_ref(record),
+                        value_casts,
+                        conflict_clause,
+                        "Failed to insert row",
+                    )
+                    .await
+                    {
+                        Ok(_) => outcome.inserted_count += 1,
+                        Err(err) => outcome.rejected.push((record.clone(), err)),
+                    }
+                }
+            }
         }
     }
+    Ok(outcome)
 }
@@ -1,7 +1,10 @@
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::stuct::batch_limits::BatchLimits;
 use crate::fileflow::stuct::db_config::DbConfig;
-use sqlx::{Error, MySql, MySqlPool, PgPool, Pool, Postgres, Sqlite, SqlitePool};
+use sqlx::pool::PoolOptions;
+use sqlx::{Database, Error, MySql, MySqlPool, PgPool, Pool, Postgres, Sqlite, SqlitePool};
 
+#[derive(Clone)]
 pub enum ConnectionEnum {
     Postgres(PgPool),
     MySQL(MySqlPool),
@@ -14,36 +17,112 @@ pub enum QueryResult {
     SQLite(Vec<sqlx::sqlite::SqliteRow>),
 }
 
+/// Cloning a connection shares its pool, which lets several workers insert at the same time
+#[derive(Clone)]
 pub struct Connection {
     pub db_config: DbConfig,
     pub connection: ConnectionEnum,
+    transactional: bool,
+    batch_limits: BatchLimits,
 }
 
 impl Connection {
     pub async fn connect(config: &DbConfig) -> Result<Self, Error> {
+        Self::open(config, false, false).await
+    }
+
+    /// Connects with a pool holding a single session, so that every statement of an import
+    /// runs on the same connection and can be wrapped in one transaction
+    pub async fn connect_transactional(config: &DbConfig) -> Result<Self, Error> {
+        Self::open(config, true, true).await
+    }
+
+    /// Connects with a pool holding a single session without any transaction, so that the settings
+    /// and the warnings of the session apply to every statement
+    pub async fn connect_session(config: &DbConfig) -> Result<Self, Error> {
+        Self::open(config, true, false).await
+    }
+
+    async fn open(
+        config: &DbConfig,
+        single_session: bool,
+        transactional: bool,
+    ) -> Result<Self, Error> {
         let connection_str: String = Self::get_connection_url(config);
 
         let connection_enum: ConnectionEnum = match config.db_driver {
             DatabaseEngine::Postgres => {
-                let pool: Pool<Postgres> = PgPool::connect(&connection_str).await?;
+                let pool: Pool<Postgres> = Self::get_pool_options(single_session)
+                    .connect(&connection_str)
+                    .await?;
                 ConnectionEnum::Postgres(pool)
             }
             DatabaseEngine::MariaDB | DatabaseEngine::MySQL => {
-                let pool: Pool<MySql> = MySqlPool::connect(&connection_str).await?;
+                let pool: Pool<MySql> = Self::get_pool_options(single_session)
+                    .connect(&connection_str)
+                    .await?;
                 ConnectionEnum::MySQL(pool)
             }
             DatabaseEngine::SQLite => {
-                let pool: Pool<Sqlite> = SqlitePool::connect(&connection_str).await?;
+                let pool: Pool<Sqlite> = Self::get_pool_options(single_session)
+                    .connect(&connection_str)
+                    .await?;
                 ConnectionEnum::SQLite(pool)
             }
         };
 
+        let batch_limits: BatchLimits = Self::fetch_batch_limits(&connection_enum, config).await;
+
         Ok(Self {
             db_config: config.clone(),
             connection: connection_enum,
+            transactional,
+            batch_limits,
         })
     }
 
+    /// MySQL refuses any statement larger than its `max_allowed_packet`, the other engines use their known limits
+    async fn fetch_batch_limits(connection: &ConnectionEnum, config: &DbConfig) -> BatchLimits {
+        let limits: BatchLimits = BatchLimits::for_engine(&config.db_driver);
+        match connection {
+            ConnectionEnum::MySQL(pool) => {
+                match sqlx::query_scalar::<_, u64>("SELECT @@max_allowed_packet")
+                    .fetch_one(pool)
+                    .await
+                {
+                    Ok(max_packet) => {
+                        limits.with_max_packet(usize::try_from(max_packet).unwrap_or(usize::MAX))
+                    }
+                    Err(err) => {
+                        eprintln!("Error: Failed to read max_allowed_packet: {err}");
+                        limits
+                    }
+                }
+            }
+            _ => limits,
+        }
+    }
+
+    /// A single session pool must never recycle its session, it would silently end its transaction or reset its settings
+    fn get_pool_options<DB: Database>(single_session: bool) -> PoolOptions<DB> {
+        if single_session {
+            return PoolOptions::<DB>::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+        PoolOptions::<DB>::new()
+    }
+
+    /// Returns true when a failed statement must abort the whole import
+    pub const fn is_transactional(&self) -> bool {
+        self.transactional
+    }
+
+    pub const fn get_batch_limits(&self) -> &BatchLimits {
+        &self.batch_limits
+    }
+
     pub const fn get_db_config(&self) -> &DbConfig {
         &self.db_config
     }
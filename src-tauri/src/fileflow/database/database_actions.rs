@@ -1,23 +1,42 @@
-use crate::fileflow::database::connection::{Connection, QueryResult};
+use crate::fileflow::database::connection::{Connection, ConnectionEnum, QueryResult};
 use crate::fileflow::database::sql_builder::{
-    build_copy_table_sql, build_create_with_fixed_size_sql, build_drop_statement_sql,
+    build_copy_table_sql, build_create_table_like_sql, build_create_with_fixed_size_sql,
+    build_drop_statement_sql, build_insert_from_table_sql, build_query_table_columns,
+    build_query_unique_keys, build_select_all_sql, build_swap_table_sql,
 };
+use crate::fileflow::enumeration::column_type::ColumnType;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::stuct::cancel_token::CancelToken;
 use crate::fileflow::stuct::download_config::DownloadConfig;
-use csv::{Writer, WriterBuilder};
-use sqlx::{Column, Row};
+use crate::fileflow::stuct::export_report::TableExport;
+use crate::fileflow::stuct::progress::{Progress, ProgressListener};
+use crate::fileflow::stuct::table_column::TableColumn;
+use csv::{StringRecord, Writer, WriterBuilder};
+use sqlx::mysql::MySqlDatabaseError;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgCopyIn, PgPoolCopyExt};
+use sqlx::{Column, Error, MySql, PgPool, Postgres, QueryBuilder, Row, Sqlite};
 use std::collections::HashMap;
 use std::fs::File;
+use std::io::Read;
+use std::time::Instant;
 
 /// Exports a table’s data into a CSV file. It uses offset/LIMIT pagination to retrieve data in batches
+/// and reports the number of exported rows after each of them, the total being unknown.
+/// Returns the file written for the table
 pub async fn export_table(
     connection: &Connection,
     download_config: &DownloadConfig,
     table_name: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    progress_listener: &dyn ProgressListener,
+    cancel_token: &CancelToken,
+) -> Result<TableExport, Box<dyn std::error::Error>> {
     const LIMIT: i32 = 5_000;
     let mut offset: i32 = 0;
     let mut header_written: bool = false;
+    let mut exported_rows: u64 = 0;
+    let start: Instant = Instant::now();
     let file_path: String = format!("{}/{table_name}_export.csv", download_config.location);
 
     let mut wtr: Writer<File> = WriterBuilder::new()
@@ -25,9 +44,20 @@ pub async fn export_table(
         .from_path(&file_path)
         .expect("Failed to create CSV writer");
 
-    let base_sql: String = format!("SELECT * FROM {table_name}");
+    let base_sql: String = build_select_all_sql(
+        &connection.get_db_config().db_driver,
+        download_config.schema.as_deref(),
+        table_name,
+    );
 
     loop {
+        // A cancelled export does not leave a truncated file behind
+        if cancel_token.is_cancelled() {
+            drop(wtr);
+            std::fs::remove_file(&file_path)?;
+            return Err("Export cancelled".into());
+        }
+
         let sql_query: String = format!("{base_sql} LIMIT {LIMIT} OFFSET {offset}");
         let query_result: QueryResult = connection.query_many_with_result(&sql_query).await?;
 
@@ -116,20 +146,38 @@ pub async fn export_table(
                 .expect("Failed to write record to CSV");
         }
 
+        exported_rows += rows.len() as u64;
+        progress_listener.on_progress(&Progress::new(
+            table_name,
+            0,
+            0,
+            exported_rows,
+            0,
+            start.elapsed(),
+        ));
+
         offset += LIMIT;
     }
 
     wtr.flush().expect("Failed to flush CSV writer");
-    Ok(())
+    let bytes_written: u64 = std::fs::metadata(&file_path)?.len();
+
+    Ok(TableExport {
+        table_name: table_name.into(),
+        file_path,
+        rows_exported: exported_rows,
+        bytes_written,
+    })
 }
 
 /// Helper function to drop a table if it exists
 pub async fn drop_table_if_exists(
     connection: &Connection,
     db_driver: &DatabaseEngine,
+    schema: Option<&str>,
     table_name: &str,
 ) -> Result<(), String> {
-    let drop_query: &str = &build_drop_statement_sql(db_driver, table_name)?;
+    let drop_query: &str = &build_drop_statement_sql(db_driver, schema, table_name)?;
     execute_query(
         connection,
         drop_query,
@@ -150,33 +198,212 @@ pub async fn execute_query(
         .map_err(|err| format!("{context}: {err}"))
 }
 
-/// Helper function to batch-insert records into a table
+/// Number of warnings raised by the last statement of a MySQL session, the connection must hold a single session
+pub async fn get_warning_count(connection: &Connection) -> Result<u64, String> {
+    let ConnectionEnum::MySQL(pool) = &connection.connection else {
+        return Err("Warnings are only counted for MySQL and MariaDB".into());
+    };
+
+    // Sent as plain text, preparing a statement would clear the warnings of the previous one
+    let warnings: i64 = sqlx::raw_sql("SELECT CAST(@@warning_count AS SIGNED)")
+        .fetch_one(pool)
+        .await
+        .and_then(|row| row.try_get(0))
+        .map_err(|err| format!("Failed to read the warnings count: {err}"))?;
+    Ok(u64::try_from(warnings).unwrap_or_default())
+}
+
+/// Helper function to batch-insert records into a table with a single statement, values are bound as query parameters.
+/// The rows are expected to fit in the limits of the engine, as split by `BatchLimits::split`;
+/// a statement still refused for its size is split in half and retried
 pub async fn batch_insert(
     connection: &Connection,
     insert_query_base: &str,
-    batch: &[String],
+    batch: &[StringRecord],
+    value_casts: &[Option<String>],
+    conflict_clause: &str,
     context: &str,
 ) -> Result<(), String> {
     if batch.is_empty() {
         return Ok(());
     }
-    let insert_query: String = format!("{insert_query_base}{}", batch.join(", "));
-    execute_query(connection, &insert_query, context).await
+
+    // Statements left to run, the next one is at the end
+    let mut pending: Vec<&[StringRecord]> = vec![batch];
+
+    while let Some(rows) = pending.pop() {
+        match insert_statement(
+            connection,
+            insert_query_base,
+            rows,
+            value_casts,
+            conflict_clause,
+        )
+        .await
+        {
+            Ok(()) => {}
+            Err(err) if rows.len() > 1 && is_statement_too_large(&err) => {
+                eprintln!(
+                    "Statement of {} rows too large, splitting it: {err}",
+                    rows.len()
+                );
+                let (first, second) = rows.split_at(rows.len() / 2);
+                pending.push(second);
+                pending.push(first);
+            }
+            Err(err) => return Err(format!("{context}: {err}")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Insert the given rows with a single statement
+async fn insert_statement(
+    connection: &Connection,
+    insert_query_base: &str,
+    rows: &[StringRecord],
+    value_casts: &[Option<String>],
+    conflict_clause: &str,
+) -> Result<(), Error> {
+    match &connection.connection {
+        ConnectionEnum::Postgres(pool) => {
+            let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(insert_query_base);
+            query_builder.push_values(rows, |mut builder, record| {
+                for (i, value) in record.iter().enumerate() {
+                    match value_casts.get(i).and_then(Option::as_ref) {
+                        Some(data_type) => {
+                            builder.push("CAST(NULLIF(");
+                            builder.push_bind_unseparated(value);
+                            builder.push_unseparated(format!(", '') AS {data_type})"));
+                        }
+                        None => {
+                            builder.push_bind(value);
+                        }
+                    }
+                }
+            });
+            query_builder.push(conflict_clause);
+            query_builder.build().execute(pool).await.map(|_| ())
+        }
+        ConnectionEnum::MySQL(pool) => {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(insert_query_base);
+            query_builder.push_values(rows, |mut builder, record| {
+                for (i, value) in record.iter().enumerate() {
+                    // The engine converts the text itself, only the empty strings must become NULL
+                    if value_casts.get(i).is_some_and(Option::is_some) {
+                        builder.push("NULLIF(");
+                        builder.push_bind_unseparated(value);
+                        builder.push_unseparated(", '')");
+                    } else {
+                        builder.push_bind(value);
+                    }
+                }
+            });
+            query_builder.push(conflict_clause);
+            query_builder.build().execute(pool).await.map(|_| ())
+        }
+        ConnectionEnum::SQLite(pool) => {
+            let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(insert_query_base);
+            query_builder.push_values(rows, |mut builder, record| {
+                for (i, value) in record.iter().enumerate() {
+                    // The engine converts the text itself, only the empty strings must become NULL
+                    if value_casts.get(i).is_some_and(Option::is_some) {
+                        builder.push("NULLIF(");
+                        builder.push_bind_unseparated(value);
+                        builder.push_unseparated(", '')");
+                    } else {
+                        builder.push_bind(value);
+                    }
+                }
+            });
+            query_builder.push(conflict_clause);
+            query_builder.build().execute(pool).await.map(|_| ())
+        }
+    }
+}
+
+/// Returns true when the engine refused a statement because of its size rather than its content, going by the error code:
+/// ER_NET_PACKET_TOO_LARGE for MySQL and MariaDB, program_limit_exceeded for Postgres and SQLITE_TOOBIG for SQLite
+fn is_statement_too_large(err: &Error) -> bool {
+    const MYSQL_PACKET_TOO_LARGE: u16 = 1153;
+    const POSTGRES_PROGRAM_LIMIT_EXCEEDED: &str = "54000";
+    const SQLITE_TOO_BIG: &str = "18";
+
+    let Error::Database(db_err) = err else {
+        return false;
+    };
+    if let Some(mysql_err) = db_err.try_downcast_ref::<MySqlDatabaseError>() {
+        return mysql_err.number() == MYSQL_PACKET_TOO_LARGE;
+    }
+    matches!(
+        db_err.code().as_deref(),
+        Some(POSTGRES_PROGRAM_LIMIT_EXCEEDED | SQLITE_TOO_BIG)
+    )
+}
+
+/// Stream csv data to Postgres through a COPY FROM STDIN statement and return the number of rows copied.
+/// `on_chunk` is called with each chunk once it is sent, an error aborts the COPY
+pub async fn copy_from_stdin(
+    connection: &Connection,
+    copy_statement: &str,
+    mut source: impl Read,
+    mut on_chunk: impl FnMut(&[u8]) -> Result<(), String>,
+) -> Result<u64, String> {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let pool: &PgPool = match &connection.connection {
+        ConnectionEnum::Postgres(pool) => pool,
+        _ => return Err("COPY FROM STDIN is only available for Postgres".into()),
+    };
+
+    let mut copy_in: PgCopyIn<PoolConnection<Postgres>> = pool
+        .copy_in_raw(copy_statement)
+        .await
+        .map_err(|err| format!("Failed to start COPY: {err}"))?;
+
+    let mut buffer: Vec<u8> = vec![0; CHUNK_SIZE];
+    loop {
+        let read: usize = match source.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) => {
+                let _ = copy_in.abort(format!("Failed to read file: {err}")).await;
+                return Err(format!("Failed to read file: {err}"));
+            }
+        };
+
+        if let Err(err) = copy_in.send(&buffer[..read]).await {
+            let _ = copy_in.abort(err.to_string()).await;
+            return Err(format!("Failed to send data to COPY: {err}"));
+        }
+        if let Err(err) = on_chunk(&buffer[..read]) {
+            let _ = copy_in.abort(err.clone()).await;
+            return Err(err);
+        }
+    }
+
+    copy_in
+        .finish()
+        .await
+        .map_err(|err| format!("Failed to finish COPY: {err}"))
 }
 
-/// Create the final table and copy data from the temporary table
+/// Create the final table and copy data from the temporary table, returns the CREATE statement of the final table
 pub async fn create_and_copy_final_table(
     connection: &Connection,
     db_driver: &DatabaseEngine,
+    schema: Option<&str>,
     final_table_name: &str,
     temporary_table_name: &str,
-    columns_size_map: &HashMap<&str, usize>,
+    column_types: &HashMap<&str, ColumnType>,
     final_columns_name: &[String],
-) -> Result<(), String> {
+) -> Result<String, String> {
     let create_final_table_query: String = build_create_with_fixed_size_sql(
         db_driver,
+        schema,
         final_table_name,
-        columns_size_map,
+        column_types,
         final_columns_name,
     );
     execute_query(
@@ -186,8 +413,14 @@ pub async fn create_and_copy_final_table(
     )
     .await?;
 
-    let copy_data_query: String =
-        build_copy_table_sql(db_driver, temporary_table_name, final_table_name);
+    let copy_data_query: String = build_copy_table_sql(
+        db_driver,
+        schema,
+        temporary_table_name,
+        final_table_name,
+        column_types,
+        final_columns_name,
+    );
 
     execute_query(
         connection,
@@ -196,19 +429,264 @@ pub async fn create_and_copy_final_table(
     )
     .await?;
 
-    Ok(())
+    Ok(create_final_table_query)
+}
+
+/// Name of the text table the optimized insert loads before creating the typed table
+pub fn get_temporary_table_name(final_table_name: &str) -> String {
+    format!("{final_table_name}_temporary")
 }
 
 /// Drop a list of tables if they exist
 pub async fn drop_existing_tables(
     connection: &Connection,
+    schema: Option<&str>,
     table_names: &[&str],
     db_driver: &DatabaseEngine,
 ) -> Result<(), String> {
     for table_name in table_names.iter() {
-        if let Err(e) = drop_table_if_exists(connection, db_driver, table_name).await {
+        if let Err(e) = drop_table_if_exists(connection, db_driver, schema, table_name).await {
             eprintln!("Error: {e}");
         }
     }
     Ok(())
 }
+
+/// Start an all-or-nothing import and return the name of the table the data must be written into.
+/// MySQL commits DDL implicitly, so the data is loaded into a shadow table that `commit_import` swaps in at the end.
+pub async fn begin_import(
+    connection: &Connection,
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+    mode: &InsertionType,
+) -> Result<String, String> {
+    match db_driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => {
+            let shadow_table_name: String = format!("{table_name}_shadow");
+            drop_table_if_exists(connection, db_driver, schema, &shadow_table_name).await?;
+
+            if !matches!(mode, InsertionType::Append | InsertionType::Upsert) {
+                return Ok(shadow_table_name);
+            }
+
+            // Append and upsert work on top of the existing rows, the shadow table starts as a copy
+            let table_exists: bool = !get_table_columns(connection, db_driver, schema, table_name)
+                .await?
+                .is_empty();
+            if !table_exists {
+                if *mode == InsertionType::Append {
+                    return Err(format!("Table '{table_name}' does not exist"));
+                }
+                return Ok(shadow_table_name);
+            }
+
+            execute_query(
+                connection,
+                &build_create_table_like_sql(schema, &shadow_table_name, table_name),
+                "Failed to create shadow table",
+            )
+            .await?;
+            execute_query(
+                connection,
+                &build_insert_from_table_sql(schema, &shadow_table_name, table_name),
+                "Failed to copy rows into shadow table",
+            )
+            .await?;
+
+            Ok(shadow_table_name)
+        }
+        DatabaseEngine::Postgres | DatabaseEngine::SQLite => {
+            execute_query(connection, "BEGIN", "Failed to start transaction").await?;
+            Ok(table_name.into())
+        }
+    }
+}
+
+/// Make the data of an all-or-nothing import visible, swapping the shadow table in place of the table for MySQL
+pub async fn commit_import(
+    connection: &Connection,
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+    target_table_name: &str,
+) -> Result<(), String> {
+    match db_driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => {
+            let table_exists: bool = !get_table_columns(connection, db_driver, schema, table_name)
+                .await?
+                .is_empty();
+            if !table_exists {
+                return execute_query(
+                    connection,
+                    &build_swap_table_sql(schema, table_name, target_table_name, None),
+                    "Failed to rename shadow table",
+                )
+                .await;
+            }
+
+            let replaced_table_name: String = format!("{table_name}_replaced");
+            drop_table_if_exists(connection, db_driver, schema, &replaced_table_name).await?;
+            execute_query(
+                connection,
+                &build_swap_table_sql(
+                    schema,
+                    table_name,
+                    target_table_name,
+                    Some(&replaced_table_name),
+                ),
+                "Failed to swap shadow table",
+            )
+            .await?;
+            drop_table_if_exists(connection, db_driver, schema, &replaced_table_name).await
+        }
+        DatabaseEngine::Postgres | DatabaseEngine::SQLite => {
+            execute_query(connection, "COMMIT", "Failed to commit transaction").await
+        }
+    }
+}
+
+/// Discard everything an all-or-nothing import wrote, the previous table is left untouched
+pub async fn rollback_import(
+    connection: &Connection,
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    target_table_name: &str,
+) -> Result<(), String> {
+    match db_driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => {
+            let temporary_table_name: String = get_temporary_table_name(target_table_name);
+            drop_table_if_exists(connection, db_driver, schema, &temporary_table_name).await?;
+            drop_table_if_exists(connection, db_driver, schema, target_table_name).await
+        }
+        DatabaseEngine::Postgres | DatabaseEngine::SQLite => {
+            execute_query(connection, "ROLLBACK", "Failed to rollback transaction").await
+        }
+    }
+}
+
+/// Fetch the columns of an existing table, an empty list means that the table does not exist.
+/// A table without a schema is looked up in the default one of the connection
+pub async fn get_table_columns(
+    connection: &Connection,
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+) -> Result<Vec<TableColumn>, String> {
+    let sql: String = build_query_table_columns(
+        db_driver,
+        schema
+            .filter(|schema| !schema.is_empty())
+            .unwrap_or_else(|| connection.get_db_config().get_default_schema()),
+        table_name,
+    );
+
+    let query_result: QueryResult = connection
+        .query_many_with_result(&sql)
+        .await
+        .map_err(|err| format!("Failed to fetch columns of table '{table_name}': {err}"))?;
+
+    let columns: Vec<TableColumn> = match query_result {
+        QueryResult::MySQL(rows) => rows
+            .iter()
+            .map(|row| {
+                to_table_column(
+                    row.get("column_name"),
+                    row.get("data_type"),
+                    row.get("is_nullable"),
+                    row.get("column_default"),
+                    row.get("max_length"),
+                )
+            })
+            .collect(),
+        QueryResult::Postgres(rows) => rows
+            .iter()
+            .map(|row| {
+                to_table_column(
+                    row.get("column_name"),
+                    row.get("data_type"),
+                    row.get("is_nullable"),
+                    row.get("column_default"),
+                    row.get("max_length"),
+                )
+            })
+            .collect(),
+        QueryResult::SQLite(rows) => rows
+            .iter()
+            .map(|row| {
+                to_table_column(
+                    row.get("column_name"),
+                    row.get("data_type"),
+                    row.get("is_nullable"),
+                    row.get("column_default"),
+                    row.get("max_length"),
+                )
+            })
+            .collect(),
+    };
+
+    Ok(columns)
+}
+
+/// Fetch the column names of each unique index or primary key of an existing table.
+/// A table without a schema is looked up in the default one of the connection
+pub async fn get_unique_keys(
+    connection: &Connection,
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+) -> Result<Vec<Vec<String>>, String> {
+    let sql: String = build_query_unique_keys(
+        db_driver,
+        schema
+            .filter(|schema| !schema.is_empty())
+            .unwrap_or_else(|| connection.get_db_config().get_default_schema()),
+        table_name,
+    );
+
+    let query_result: QueryResult = connection
+        .query_many_with_result(&sql)
+        .await
+        .map_err(|err| format!("Failed to fetch unique keys of table '{table_name}': {err}"))?;
+
+    let index_columns: Vec<(String, String)> = match query_result {
+        QueryResult::MySQL(rows) => rows
+            .iter()
+            .map(|row| (row.get("index_name"), row.get("column_name")))
+            .collect(),
+        QueryResult::Postgres(rows) => rows
+            .iter()
+            .map(|row| (row.get("index_name"), row.get("column_name")))
+            .collect(),
+        QueryResult::SQLite(rows) => rows
+            .iter()
+            .map(|row| (row.get("index_name"), row.get("column_name")))
+            .collect(),
+    };
+
+    let mut keys: Vec<(String, Vec<String>)> = Vec::new();
+    for (index_name, column_name) in index_columns {
+        match keys.iter_mut().find(|(name, _)| *name == index_name) {
+            Some((_, columns)) => columns.push(column_name),
+            None => keys.push((index_name, vec![column_name])),
+        }
+    }
+    Ok(keys.into_iter().map(|(_, columns)| columns).collect())
+}
+
+/// Build a TableColumn from the raw values returned by the catalog query
+fn to_table_column(
+    name: String,
+    data_type: String,
+    is_nullable: String,
+    column_default: Option<String>,
+    max_length: Option<i64>,
+) -> TableColumn {
+    TableColumn {
+        name,
+        data_type: data_type.to_lowercase(),
+        max_length: max_length.and_then(|length| usize::try_from(length).ok()),
+        is_nullable: is_nullable.eq_ignore_ascii_case("YES"),
+        has_default: column_default.is_some(),
+    }
+}
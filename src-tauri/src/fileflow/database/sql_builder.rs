@@ -1,103 +1,300 @@
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
 use std::collections::HashMap;
 
+/// Quote a table or column name for different database drivers, the quote characters it contains are doubled.
+pub fn quote_identifier(driver: &DatabaseEngine, identifier: &str) -> String {
+    let quote: char = match driver {
+        DatabaseEngine::SQLite | DatabaseEngine::Postgres => '"',
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => '`',
+    };
+    let escaped: String = identifier.replace(quote, &format!("{quote}{quote}"));
+    format!("{quote}{escaped}{quote}")
+}
+
+/// Name of a table qualified by its schema, if any, for display only: a table name may itself contain a dot.
+pub fn get_qualified_table_name(schema: Option<&str>, table_name: &str) -> String {
+    match schema {
+        Some(schema) if !schema.is_empty() => format!("{schema}.{table_name}"),
+        _ => table_name.into(),
+    }
+}
+
+/// Quote a table name qualified by its schema, if any, for different database drivers, each part being quoted on its own.
+pub fn quote_table_name(driver: &DatabaseEngine, schema: Option<&str>, table_name: &str) -> String {
+    match schema {
+        Some(schema) if !schema.is_empty() => format!(
+            "{}.{}",
+            quote_identifier(driver, schema),
+            quote_identifier(driver, table_name)
+        ),
+        _ => quote_identifier(driver, table_name),
+    }
+}
+
+/// Quote each name of a list and join them into a column list.
+fn quote_identifiers(driver: &DatabaseEngine, identifiers: &[String]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| quote_identifier(driver, identifier))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 /// This function is used to generate the DROP TABLE statement for different database drivers.
 pub fn build_drop_statement_sql(
     db_driver: &DatabaseEngine,
+    schema: Option<&str>,
     final_table_name: &str,
 ) -> Result<String, String> {
-    match db_driver {
-        DatabaseEngine::SQLite | DatabaseEngine::Postgres => {
-            Ok(format!("DROP TABLE IF EXISTS \"{final_table_name}\""))
-        }
-        DatabaseEngine::MariaDB | DatabaseEngine::MySQL => {
-            Ok(format!("DROP TABLE IF EXISTS `{final_table_name}`"))
-        }
-    }
+    Ok(format!(
+        "DROP TABLE IF EXISTS {}",
+        quote_table_name(db_driver, schema, final_table_name)
+    ))
+}
+
+/// This function is used to generate the SELECT statement reading every row of a table for different database drivers.
+pub fn build_select_all_sql(
+    db_driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+) -> String {
+    format!(
+        "SELECT * FROM {}",
+        quote_table_name(db_driver, schema, table_name)
+    )
 }
 
-/// This function is used to generate the INSERT INTO statement for different database drivers.
+/// This function is used to generate the INSERT INTO statement for different database drivers, the VALUES are pushed by the query builder.
 pub fn build_prepared_statement_sql(
     db_driver: &DatabaseEngine,
+    schema: Option<&str>,
     table_name: &str,
     columns: &[String],
 ) -> String {
-    let quote: char = match db_driver {
-        DatabaseEngine::SQLite | DatabaseEngine::Postgres => '\"',
-        DatabaseEngine::MariaDB | DatabaseEngine::MySQL => '`',
-    };
-    let mut query = format!("INSERT INTO {quote}{table_name}{quote} (");
-    for column in columns.iter() {
-        if column.is_empty() {
-            continue;
+    let columns: Vec<String> = columns
+        .iter()
+        .filter(|column| !column.is_empty())
+        .cloned()
+        .collect();
+    format!(
+        "INSERT INTO {} ({}) ",
+        quote_table_name(db_driver, schema, table_name),
+        quote_identifiers(db_driver, &columns)
+    )
+}
+
+/// Get the type of the column a bound text value is stored in when it isn't a text column, empty strings then becoming NULL.
+/// Postgres binds text parameters as TEXT and does not convert them implicitly, so the value is cast to that type.
+/// MySQL and SQLite convert the text themselves, the parameters are stripped from the type and a SQLite column
+/// without a declared type or with a text affinity keeps the text as is.
+pub fn build_value_cast(driver: &DatabaseEngine, data_type: &str) -> Option<String> {
+    const POSTGRES_TEXT_TYPES: [&str; 5] = [
+        "text",
+        "character varying",
+        "character",
+        "user-defined",
+        "array",
+    ];
+    const MYSQL_TEXT_TYPES: [&str; 14] = [
+        "char",
+        "varchar",
+        "tinytext",
+        "text",
+        "mediumtext",
+        "longtext",
+        "enum",
+        "set",
+        "binary",
+        "varbinary",
+        "tinyblob",
+        "blob",
+        "mediumblob",
+        "longblob",
+    ];
+    const SQLITE_TEXT_AFFINITIES: [&str; 4] = ["char", "clob", "text", "blob"];
+
+    let data_type: String = data_type.trim().to_lowercase();
+    let name: &str = data_type
+        .split_once('(')
+        .map_or(data_type.as_str(), |(name, _)| name.trim());
+    let is_textual: bool = match driver {
+        DatabaseEngine::Postgres => POSTGRES_TEXT_TYPES.contains(&data_type.as_str()),
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => MYSQL_TEXT_TYPES.contains(&name),
+        DatabaseEngine::SQLite => {
+            name.is_empty()
+                || SQLITE_TEXT_AFFINITIES
+                    .iter()
+                    .any(|affinity| name.contains(affinity))
         }
-        query.push_str(&format!("{quote}{column}{quote}"));
-        if column != columns.last().unwrap() {
-            query.push_str(", ");
+    };
+    match driver {
+        _ if is_textual => None,
+        DatabaseEngine::Postgres => Some(data_type),
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB | DatabaseEngine::SQLite => {
+            Some(name.into())
         }
     }
-    query.push_str(") VALUES ");
-    query
 }
 
 /// This function is used to generate the COPY statement for different database drivers.
+/// Values of the temporary table are converted to the type of the final column, empty strings becoming NULL.
 pub fn build_copy_table_sql(
     db_driver: &DatabaseEngine,
+    schema: Option<&str>,
     temporary_table_name: &str,
     final_table_name: &str,
+    map_column_type: &HashMap<&str, ColumnType>,
+    snake_case_headers: &[String],
 ) -> String {
-    let quote: char = match db_driver {
-        DatabaseEngine::SQLite | DatabaseEngine::Postgres => '\"',
-        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => '`',
-    };
+    let mut columns: Vec<String> = Vec::with_capacity(snake_case_headers.len());
+    let mut values: Vec<String> = Vec::with_capacity(snake_case_headers.len());
+
+    for header in snake_case_headers {
+        let column: String = quote_identifier(db_driver, header);
+        let value: String =
+            build_value_conversion_sql(db_driver, &column, map_column_type.get(header.as_str()));
+        columns.push(column);
+        values.push(value);
+    }
+
     format!(
-        "INSERT INTO {quote}{final_table_name}{quote} SELECT * FROM {quote}{temporary_table_name}{quote}"
+        "INSERT INTO {} ({}) SELECT {} FROM {}",
+        quote_table_name(db_driver, schema, final_table_name),
+        columns.join(", "),
+        values.join(", "),
+        quote_table_name(db_driver, schema, temporary_table_name)
     )
 }
 
-/// This function is used to generate the CREATE TABLE statement with fixed size for each column for different database drivers.
+/// Convert a text column to its inferred type, empty strings becoming NULL
+fn build_value_conversion_sql(
+    db_driver: &DatabaseEngine,
+    column: &str,
+    column_type: Option<&ColumnType>,
+) -> String {
+    match column_type {
+        Some(ColumnType::Boolean) => format!(
+            "CASE WHEN LOWER({column}) IN ('true', 't', 'yes', 'y') THEN TRUE WHEN LOWER({column}) IN ('false', 'f', 'no', 'n') THEN FALSE END"
+        ),
+        Some(column_type) if !column_type.is_textual() => match db_driver {
+            DatabaseEngine::Postgres => format!(
+                "CAST(NULLIF({column}, '') AS {})",
+                build_column_type_sql(db_driver, column_type)
+            ),
+            DatabaseEngine::SQLite | DatabaseEngine::MySQL | DatabaseEngine::MariaDB => {
+                format!("NULLIF({column}, '')")
+            }
+        },
+        _ => column.into(),
+    }
+}
+
+/// Generate the Postgres ALTER TABLE statement converting the text columns of a table to their inferred type in place.
+/// `None` when every column stays TEXT.
+pub fn build_alter_column_types_sql(
+    schema: Option<&str>,
+    table_name: &str,
+    map_column_type: &HashMap<&str, ColumnType>,
+    snake_case_headers: &[String],
+) -> Option<String> {
+    let driver: &DatabaseEngine = &DatabaseEngine::Postgres;
+    let alterations: Vec<String> = snake_case_headers
+        .iter()
+        .filter_map(|header| {
+            let column_type: &ColumnType = map_column_type.get(header.as_str())?;
+            let column: String = quote_identifier(driver, header);
+            match column_type {
+                ColumnType::Text => None,
+                ColumnType::Varchar(_) => Some(format!(
+                    "ALTER COLUMN {column} TYPE {}",
+                    build_column_type_sql(driver, column_type)
+                )),
+                _ => Some(format!(
+                    "ALTER COLUMN {column} TYPE {} USING {}",
+                    build_column_type_sql(driver, column_type),
+                    build_value_conversion_sql(driver, &column, Some(column_type))
+                )),
+            }
+        })
+        .collect();
+
+    if alterations.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "ALTER TABLE {} {}",
+        quote_table_name(driver, schema, table_name),
+        alterations.join(", ")
+    ))
+}
+
+/// Generate the ALTER TABLE statement adding a column of the csv file missing from an existing table.
+pub fn build_add_column_sql(
+    driver: &DatabaseEngine,
+    schema: Option<&str>,
+    table_name: &str,
+    column: &str,
+    column_type: &ColumnType,
+) -> String {
+    format!(
+        "ALTER TABLE {} ADD COLUMN {} {}",
+        quote_table_name(driver, schema, table_name),
+        quote_identifier(driver, column),
+        build_column_type_sql(driver, column_type)
+    )
+}
+
+/// This function is used to get the SQL type of a column for different database drivers.
+pub fn build_column_type_sql(driver: &DatabaseEngine, column_type: &ColumnType) -> String {
+    match (column_type, driver) {
+        (ColumnType::Boolean, _) => "BOOLEAN".into(),
+        (ColumnType::Integer, DatabaseEngine::MySQL | DatabaseEngine::MariaDB) => "INT".into(),
+        (ColumnType::Integer, DatabaseEngine::SQLite | DatabaseEngine::Postgres) => {
+            "INTEGER".into()
+        }
+        (ColumnType::BigInt, _) => "BIGINT".into(),
+        (ColumnType::Decimal { precision, scale }, _) => format!("DECIMAL({precision},{scale})"),
+        (ColumnType::Date, _) => "DATE".into(),
+        (ColumnType::Timestamp, DatabaseEngine::MySQL | DatabaseEngine::MariaDB) => {
+            "DATETIME(6)".into()
+        }
+        (ColumnType::Timestamp, DatabaseEngine::SQLite | DatabaseEngine::Postgres) => {
+            "TIMESTAMP".into()
+        }
+        (ColumnType::Varchar(length), _) => format!("VARCHAR({length})"),
+        (ColumnType::Text, _) => "TEXT".into(),
+    }
+}
+
+/// This function is used to generate the CREATE TABLE statement with the inferred type of each column for different database drivers.
 pub fn build_create_with_fixed_size_sql(
     driver: &DatabaseEngine,
+    schema: Option<&str>,
     final_table_name: &str,
-    map_column_max_length: &HashMap<&str, usize>,
+    map_column_type: &HashMap<&str, ColumnType>,
     snake_case_headers: &[String],
 ) -> String {
-    // Constants
-    const MAX_VARCHAR_LENGTH: usize = 255;
-    const TEXT_TYPE: &str = "TEXT";
-    const VARCHAR_TYPE: &str = "VARCHAR";
-
-    // Determine database-specific formatting
-    let quote: char = match driver {
-        DatabaseEngine::SQLite | DatabaseEngine::Postgres => '"',
-        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => '`',
-    };
-
     let mut columns: Vec<String> = Vec::with_capacity(snake_case_headers.len());
     let mut total_length: usize = 0;
 
     // Build column definitions
     for header in snake_case_headers {
-        let max_length = map_column_max_length
-            .get(header.as_str())
-            .copied()
-            .unwrap_or(MAX_VARCHAR_LENGTH);
-
-        // Determine column type
-        let type_str: String = if max_length <= MAX_VARCHAR_LENGTH {
-            format!("{VARCHAR_TYPE}({max_length})") // VARCHAR(n)
-        } else {
-            TEXT_TYPE.into()
+        let type_str: String = match map_column_type.get(header.as_str()) {
+            Some(column_type) => build_column_type_sql(driver, column_type),
+            None => build_column_type_sql(driver, &ColumnType::Varchar(255)),
         };
 
         // Format column definition
-        let column: String = format!("{0}{1}{0} {2}", quote, header, type_str);
+        let column: String = format!("{} {type_str}", quote_identifier(driver, header));
         total_length += column.len();
         columns.push(column);
     }
 
     // Pre-calculate final string capacity
-    let table_quoted: String = format!("{0}{1}{0}", quote, final_table_name);
+    let table_quoted: String = quote_table_name(driver, schema, final_table_name);
     let mut result: String = String::with_capacity(
         15 + // "CREATE TABLE  ();"
             table_quoted.len() +
@@ -118,76 +315,291 @@ pub fn build_create_with_fixed_size_sql(
 /// This function is used to generate the CREATE TABLE statement for different database drivers.
 pub fn build_create_table_sql(
     driver: &DatabaseEngine,
+    schema: Option<&str>,
     final_table_name: &str,
     snake_case_headers: &[String],
 ) -> String {
-    match driver {
-        DatabaseEngine::SQLite | DatabaseEngine::Postgres => format!(
-            "CREATE TABLE \"{final_table_name}\" ({})",
-            snake_case_headers
+    format!(
+        "CREATE TABLE {} ({})",
+        quote_table_name(driver, schema, final_table_name),
+        snake_case_headers
+            .iter()
+            .map(|h| format!("{} TEXT", quote_identifier(driver, h)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// This function is used to generate the CREATE TABLE statement with a primary key on the given columns for different database drivers.
+pub fn build_create_table_with_primary_key_sql(
+    driver: &DatabaseEngine,
+    schema: Option<&str>,
+    final_table_name: &str,
+    snake_case_headers: &[String],
+    key_columns: &[String],
+) -> String {
+    // MySQL can't index a TEXT column without a prefix length
+    let key_type: &str = match driver {
+        DatabaseEngine::SQLite | DatabaseEngine::Postgres => "TEXT",
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => "VARCHAR(255)",
+    };
+
+    let columns: Vec<String> = snake_case_headers
+        .iter()
+        .map(|h| {
+            let type_str: &str = if key_columns.contains(h) {
+                key_type
+            } else {
+                "TEXT"
+            };
+            format!("{} {type_str}", quote_identifier(driver, h))
+        })
+        .collect();
+
+    format!(
+        "CREATE TABLE {} ({}, PRIMARY KEY ({}))",
+        quote_table_name(driver, schema, final_table_name),
+        columns.join(", "),
+        quote_identifiers(driver, key_columns)
+    )
+}
+
+/// This function is used to generate the clause appended to an INSERT statement to resolve key conflicts for different database drivers.
+pub fn build_upsert_clause_sql(
+    driver: &DatabaseEngine,
+    columns: &[String],
+    key_columns: &[String],
+    policy: &ConflictPolicy,
+) -> String {
+    let updated_columns: Vec<&String> = columns
+        .iter()
+        .filter(|column| !key_columns.contains(column))
+        .collect();
+
+    match (driver, policy) {
+        (_, ConflictPolicy::Error) => String::new(),
+        (DatabaseEngine::SQLite | DatabaseEngine::Postgres, _) => {
+            let target: String = quote_identifiers(driver, key_columns);
+
+            if *policy == ConflictPolicy::Ignore || updated_columns.is_empty() {
+                return format!(" ON CONFLICT ({target}) DO NOTHING");
+            }
+
+            let assignments: Vec<String> = updated_columns
                 .iter()
-                .map(|h| format!("{h} TEXT"))
-                .collect::<Vec<String>>()
-                .join(", ")
-        ),
-        DatabaseEngine::MariaDB | DatabaseEngine::MySQL => format!(
-            "CREATE TABLE `{final_table_name}` ({})",
-            snake_case_headers
+                .map(|c| {
+                    let column: String = quote_identifier(driver, c);
+                    format!("{column} = EXCLUDED.{column}")
+                })
+                .collect();
+            format!(
+                " ON CONFLICT ({target}) DO UPDATE SET {}",
+                assignments.join(", ")
+            )
+        }
+        (DatabaseEngine::MySQL | DatabaseEngine::MariaDB, _) => {
+            // A self-assignment of the first key column turns the duplicate row into a no-op
+            if *policy == ConflictPolicy::Ignore || updated_columns.is_empty() {
+                let key: String = quote_identifier(driver, key_columns.first().map_or("", |k| k));
+                return format!(" ON DUPLICATE KEY UPDATE {key} = {key}");
+            }
+
+            let assignments: Vec<String> = updated_columns
                 .iter()
-                .map(|h| format!("`{h}` TEXT"))
-                .collect::<Vec<String>>()
-                .join(", ")
+                .map(|c| {
+                    let column: String = quote_identifier(driver, c);
+                    format!("{column} = VALUES({column})")
+                })
+                .collect();
+            format!(" ON DUPLICATE KEY UPDATE {}", assignments.join(", "))
+        }
+    }
+}
+
+/// This function is used to generate the Postgres COPY FROM STDIN statement loading a csv file with a header line.
+/// Unquoted empty values are kept as empty strings, like the INSERT path does.
+pub fn build_copy_from_stdin_sql(
+    schema: Option<&str>,
+    final_table_name: &str,
+    snake_case_headers: &[String],
+    dialect: &CsvDialect,
+) -> String {
+    let db_driver: &DatabaseEngine = &DatabaseEngine::Postgres;
+    let columns: String = quote_identifiers(db_driver, snake_case_headers);
+    let literal = |value: char| value.to_string().replace('\'', "''");
+    let escape: String = dialect
+        .escape
+        .map(|escape| format!(", ESCAPE '{}'", literal(escape)))
+        .unwrap_or_default();
+
+    format!(
+        "COPY {} ({columns}) FROM STDIN (FORMAT csv, HEADER {}, DELIMITER '{}', QUOTE '{}'{escape}, FORCE_NOT_NULL ({columns}))",
+        quote_table_name(db_driver, schema, final_table_name),
+        dialect.has_headers,
+        literal(dialect.separator),
+        literal(dialect.quote),
+    )
+}
+
+/// This function is used to generate the MySQL statement creating a shadow table with the same columns and keys as an existing table.
+pub fn build_create_table_like_sql(
+    schema: Option<&str>,
+    table_name: &str,
+    source_table_name: &str,
+) -> String {
+    let db_driver: &DatabaseEngine = &DatabaseEngine::MySQL;
+    format!(
+        "CREATE TABLE {} LIKE {}",
+        quote_table_name(db_driver, schema, table_name),
+        quote_table_name(db_driver, schema, source_table_name)
+    )
+}
+
+/// This function is used to generate the MySQL statement copying every row of a table into another one with the same columns.
+pub fn build_insert_from_table_sql(
+    schema: Option<&str>,
+    table_name: &str,
+    source_table_name: &str,
+) -> String {
+    let db_driver: &DatabaseEngine = &DatabaseEngine::MySQL;
+    format!(
+        "INSERT INTO {} SELECT * FROM {}",
+        quote_table_name(db_driver, schema, table_name),
+        quote_table_name(db_driver, schema, source_table_name)
+    )
+}
+
+/// This function is used to generate the MySQL statement atomically swapping a shadow table in place of a table,
+/// the replaced table being renamed when it exists.
+pub fn build_swap_table_sql(
+    schema: Option<&str>,
+    table_name: &str,
+    shadow_table_name: &str,
+    replaced_table_name: Option<&str>,
+) -> String {
+    let quote = |table_name: &str| quote_table_name(&DatabaseEngine::MySQL, schema, table_name);
+    match replaced_table_name {
+        Some(replaced) => format!(
+            "RENAME TABLE {} TO {}, {} TO {}",
+            quote(table_name),
+            quote(replaced),
+            quote(shadow_table_name),
+            quote(table_name)
+        ),
+        None => format!(
+            "RENAME TABLE {} TO {}",
+            quote(shadow_table_name),
+            quote(table_name)
         ),
     }
 }
 
-/// Get the query to fetch all tables from the database for different drivers
+/// Get the query to fetch all tables of a schema for different drivers
 pub fn build_query_all_tables(driver: &DatabaseEngine, schema: &str) -> String {
     let query: String = match driver {
-        &DatabaseEngine::MySQL | &DatabaseEngine::MariaDB => format!(
-            "SELECT TABLE_NAME FROM information_schema.TABLES WHERE TABLE_SCHEMA = '{schema}';"
+        &DatabaseEngine::MySQL | &DatabaseEngine::MariaDB | &DatabaseEngine::Postgres => format!(
+            "SELECT TABLE_NAME FROM information_schema.TABLES WHERE TABLE_SCHEMA = '{}';",
+            schema.replace('\'', "''")
+        ),
+        &DatabaseEngine::SQLite => format!(
+            "SELECT name FROM {}.sqlite_master WHERE type='table';",
+            quote_identifier(driver, schema)
         ),
-        &DatabaseEngine::Postgres => {
-            "SELECT TABLE_NAME FROM information_schema.TABLES WHERE TABLE_SCHEMA = 'public';".into()
-        }
-        &DatabaseEngine::SQLite => "SELECT name FROM sqlite_master WHERE type='table';".into(),
     };
     query
 }
 
+/// Get the query to fetch the schemas of the database for different drivers, the system ones are left out
+pub fn build_query_all_schemas(driver: &DatabaseEngine) -> String {
+    match driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => "SELECT SCHEMA_NAME AS schema_name FROM information_schema.SCHEMATA WHERE SCHEMA_NAME NOT IN ('information_schema', 'mysql', 'performance_schema', 'sys') ORDER BY SCHEMA_NAME;".into(),
+        DatabaseEngine::Postgres => "SELECT schema_name::text AS schema_name FROM information_schema.schemata WHERE schema_name NOT IN ('information_schema', 'pg_catalog') AND schema_name NOT LIKE 'pg\\_toast%' AND schema_name NOT LIKE 'pg\\_temp%' ORDER BY schema_name;".into(),
+        DatabaseEngine::SQLite => "SELECT name AS schema_name FROM pragma_database_list ORDER BY seq;".into(),
+    }
+}
+
+/// Get the query to fetch the columns of a table of the given schema for different drivers
+pub fn build_query_table_columns(
+    driver: &DatabaseEngine,
+    schema: &str,
+    table_name: &str,
+) -> String {
+    let schema: String = schema.replace('\'', "''");
+    let table_name: String = table_name.replace('\'', "''");
+    match driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => format!(
+            "SELECT COLUMN_NAME AS column_name, DATA_TYPE AS data_type, IS_NULLABLE AS is_nullable, COLUMN_DEFAULT AS column_default, CAST(CHARACTER_MAXIMUM_LENGTH AS SIGNED) AS max_length FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = '{schema}' AND TABLE_NAME = '{table_name}' ORDER BY ORDINAL_POSITION;"
+        ),
+        DatabaseEngine::Postgres => format!(
+            "SELECT column_name::text AS column_name, data_type::text AS data_type, is_nullable::text AS is_nullable, column_default::text AS column_default, character_maximum_length::bigint AS max_length FROM information_schema.columns WHERE table_schema = '{schema}' AND table_name = '{table_name}' ORDER BY ordinal_position;"
+        ),
+        DatabaseEngine::SQLite => format!(
+            "SELECT name AS column_name, type AS data_type, CASE WHEN \"notnull\" = 1 THEN 'NO' ELSE 'YES' END AS is_nullable, dflt_value AS column_default, NULL AS max_length FROM pragma_table_info('{table_name}', '{schema}');"
+        ),
+    }
+}
+
+/// Get the query to fetch the columns of each unique index or primary key of a table, one row per index column.
+/// Partial and expression indexes can't back an upsert and are left out, as are the included columns of a Postgres index.
+/// A SQLite INTEGER PRIMARY KEY is the rowid of the table and has no index of its own.
+pub fn build_query_unique_keys(driver: &DatabaseEngine, schema: &str, table_name: &str) -> String {
+    let schema: String = schema.replace('\'', "''");
+    let table_name: String = table_name.replace('\'', "''");
+    match driver {
+        DatabaseEngine::MySQL | DatabaseEngine::MariaDB => format!(
+            "SELECT INDEX_NAME AS index_name, COALESCE(COLUMN_NAME, '') AS column_name FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = '{schema}' AND TABLE_NAME = '{table_name}' AND NON_UNIQUE = 0 ORDER BY INDEX_NAME, SEQ_IN_INDEX;"
+        ),
+        DatabaseEngine::Postgres => format!(
+            "SELECT i.relname::text AS index_name, a.attname::text AS column_name FROM pg_index x JOIN pg_class t ON t.oid = x.indrelid JOIN pg_namespace n ON n.oid = t.relnamespace JOIN pg_class i ON i.oid = x.indexrelid JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY((x.indkey::int2[])[0:x.indnkeyatts - 1]) WHERE x.indisunique AND x.indpred IS NULL AND x.indexprs IS NULL AND n.nspname = '{schema}' AND t.relname = '{table_name}';"
+        ),
+        DatabaseEngine::SQLite => format!(
+            "SELECT il.name AS index_name, COALESCE(ii.name, '') AS column_name FROM pragma_index_list('{table_name}', '{schema}') AS il, pragma_index_info(il.name, '{schema}') AS ii WHERE il.\"unique\" = 1 AND il.partial = 0 UNION ALL SELECT 'rowid' AS index_name, name AS column_name FROM pragma_table_info('{table_name}', '{schema}') WHERE pk = 1 AND UPPER(type) = 'INTEGER' AND (SELECT COUNT(*) FROM pragma_table_info('{table_name}', '{schema}') WHERE pk > 0) = 1;"
+        ),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::fileflow::database::sql_builder::{
-        build_create_table_sql, build_create_with_fixed_size_sql, build_drop_statement_sql,
-        build_prepared_statement_sql, build_query_all_tables,
+        build_add_column_sql, build_alter_column_types_sql, build_column_type_sql,
+        build_copy_from_stdin_sql, build_copy_table_sql, build_create_table_like_sql,
+        build_create_table_sql, build_create_table_with_primary_key_sql,
+        build_create_with_fixed_size_sql, build_drop_statement_sql, build_insert_from_table_sql,
+        build_prepared_statement_sql, build_query_all_schemas, build_query_all_tables,
+        build_query_table_columns, build_query_unique_keys, build_select_all_sql,
+        build_swap_table_sql, build_upsert_clause_sql, build_value_cast, get_qualified_table_name,
+        quote_identifier, quote_table_name,
     };
+    use crate::fileflow::enumeration::column_type::ColumnType;
+    use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
     use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+    use crate::fileflow::stuct::csv_dialect::CsvDialect;
     use std::collections::HashMap;
 
     #[tokio::test]
     async fn test_get_drop_statement() {
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::SQLite, "table_name").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::SQLite, None, "table_name").unwrap(),
             "DROP TABLE IF EXISTS \"table_name\""
         );
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::MySQL, "table_name").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::MySQL, None, "table_name").unwrap(),
             "DROP TABLE IF EXISTS `table_name`"
         );
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::Postgres, "table_name").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::Postgres, None, "table_name").unwrap(),
             "DROP TABLE IF EXISTS \"table_name\""
         );
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::SQLite, "").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::SQLite, None, "").unwrap(),
             "DROP TABLE IF EXISTS \"\""
         );
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::MySQL, "").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::MySQL, None, "").unwrap(),
             "DROP TABLE IF EXISTS ``"
         );
         assert_eq!(
-            build_drop_statement_sql(&DatabaseEngine::Postgres, "").unwrap(),
+            build_drop_statement_sql(&DatabaseEngine::Postgres, None, "").unwrap(),
             "DROP TABLE IF EXISTS \"\""
         );
     }
@@ -197,60 +609,159 @@ mod test {
         assert_eq!(
             build_prepared_statement_sql(
                 &DatabaseEngine::SQLite,
+                None,
                 "table_name",
                 &["columns".into()]
             ),
-            "INSERT INTO \"table_name\" (\"columns\") VALUES "
+            "INSERT INTO \"table_name\" (\"columns\") "
         );
         assert_eq!(
-            build_prepared_statement_sql(&DatabaseEngine::MySQL, "table_name", &["columns".into()]),
-            "INSERT INTO `table_name` (`columns`) VALUES "
+            build_prepared_statement_sql(
+                &DatabaseEngine::MySQL,
+                None,
+                "table_name",
+                &["columns".into()]
+            ),
+            "INSERT INTO `table_name` (`columns`) "
         );
         assert_eq!(
             build_prepared_statement_sql(
                 &DatabaseEngine::Postgres,
+                None,
                 "table_name",
                 &["columns".into()]
             ),
-            "INSERT INTO \"table_name\" (\"columns\") VALUES "
+            "INSERT INTO \"table_name\" (\"columns\") "
         );
 
         assert_eq!(
-            build_prepared_statement_sql(&DatabaseEngine::SQLite, "table_name", &["".into()]),
-            "INSERT INTO \"table_name\" () VALUES "
+            build_prepared_statement_sql(&DatabaseEngine::SQLite, None, "table_name", &["".into()]),
+            "INSERT INTO \"table_name\" () "
         );
         assert_eq!(
-            build_prepared_statement_sql(&DatabaseEngine::MySQL, "table_name", &["".into()]),
-            "INSERT INTO `table_name` () VALUES "
+            build_prepared_statement_sql(&DatabaseEngine::MySQL, None, "table_name", &["".into()]),
+            "INSERT INTO `table_name` () "
         );
         assert_eq!(
-            build_prepared_statement_sql(&DatabaseEngine::Postgres, "table_name", &["".into()]),
-            "INSERT INTO \"table_name\" () VALUES "
+            build_prepared_statement_sql(
+                &DatabaseEngine::Postgres,
+                None,
+                "table_name",
+                &["".into()]
+            ),
+            "INSERT INTO \"table_name\" () "
         );
 
         assert_eq!(
             build_prepared_statement_sql(
                 &DatabaseEngine::SQLite,
+                None,
                 "table_name",
                 &["header1".into(), "header2".into()]
             ),
-            "INSERT INTO \"table_name\" (\"header1\", \"header2\") VALUES "
+            "INSERT INTO \"table_name\" (\"header1\", \"header2\") "
         );
         assert_eq!(
             build_prepared_statement_sql(
                 &DatabaseEngine::MySQL,
+                None,
                 "table_name",
                 &["header1".into(), "header2".into()]
             ),
-            "INSERT INTO `table_name` (`header1`, `header2`) VALUES "
+            "INSERT INTO `table_name` (`header1`, `header2`) "
         );
         assert_eq!(
             build_prepared_statement_sql(
                 &DatabaseEngine::Postgres,
+                None,
                 "table_name",
                 &["header1".into(), "header2".into()]
             ),
-            "INSERT INTO \"table_name\" (\"header1\", \"header2\") VALUES "
+            "INSERT INTO \"table_name\" (\"header1\", \"header2\") "
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_identifier() {
+        assert_eq!(
+            quote_identifier(&DatabaseEngine::Postgres, "order"),
+            "\"order\""
+        );
+        assert_eq!(
+            quote_identifier(&DatabaseEngine::SQLite, "my \"table\""),
+            "\"my \"\"table\"\"\""
+        );
+        assert_eq!(
+            quote_identifier(&DatabaseEngine::MySQL, "my `table`"),
+            "`my ``table```"
+        );
+        assert_eq!(
+            quote_identifier(&DatabaseEngine::MariaDB, "it's \"ok\""),
+            "`it's \"ok\"`"
+        );
+        assert_eq!(
+            build_create_table_sql(&DatabaseEngine::Postgres, None, "t\"1", &["select".into()]),
+            "CREATE TABLE \"t\"\"1\" (\"select\" TEXT)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_table_name() {
+        assert_eq!(
+            get_qualified_table_name(Some("staging"), "orders"),
+            "staging.orders"
+        );
+        assert_eq!(get_qualified_table_name(Some(""), "orders"), "orders");
+        assert_eq!(get_qualified_table_name(None, "orders"), "orders");
+        assert_eq!(
+            quote_table_name(&DatabaseEngine::Postgres, Some("staging"), "orders"),
+            "\"staging\".\"orders\""
+        );
+        assert_eq!(
+            quote_table_name(&DatabaseEngine::MySQL, Some("raw"), "orders"),
+            "`raw`.`orders`"
+        );
+        // A dot is part of the table name, only the schema qualifies it
+        assert_eq!(
+            quote_table_name(&DatabaseEngine::Postgres, None, "sales.2024"),
+            "\"sales.2024\""
+        );
+        assert_eq!(
+            quote_table_name(&DatabaseEngine::MySQL, Some("raw"), "sales.2024"),
+            "`raw`.`sales.2024`"
+        );
+        assert_eq!(
+            quote_table_name(&DatabaseEngine::SQLite, Some(""), "orders"),
+            "\"orders\""
+        );
+        assert_eq!(
+            build_drop_statement_sql(&DatabaseEngine::Postgres, Some("staging"), "orders").unwrap(),
+            "DROP TABLE IF EXISTS \"staging\".\"orders\""
+        );
+        assert_eq!(
+            build_create_table_sql(
+                &DatabaseEngine::Postgres,
+                Some("raw"),
+                "orders",
+                &["id".into()]
+            ),
+            "CREATE TABLE \"raw\".\"orders\" (\"id\" TEXT)"
+        );
+        assert_eq!(
+            build_copy_from_stdin_sql(Some("staging"), "orders", &["id".into()], &CsvDialect::default()),
+            "COPY \"staging\".\"orders\" (\"id\") FROM STDIN (FORMAT csv, HEADER true, DELIMITER ',', QUOTE '\"', FORCE_NOT_NULL (\"id\"))"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_select_all_sql() {
+        assert_eq!(
+            build_select_all_sql(&DatabaseEngine::Postgres, None, "user"),
+            "SELECT * FROM \"user\""
+        );
+        assert_eq!(
+            build_select_all_sql(&DatabaseEngine::MySQL, None, "user"),
+            "SELECT * FROM `user`"
         );
     }
 
@@ -258,30 +769,60 @@ mod test {
     async fn test_get_create_statement() {
         let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::SQLite, "table_name", &snake_case_headers),
-            "CREATE TABLE \"table_name\" (header1 TEXT, header2 TEXT)"
+            build_create_table_sql(
+                &DatabaseEngine::SQLite,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
+            "CREATE TABLE \"table_name\" (\"header1\" TEXT, \"header2\" TEXT)"
         );
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::MySQL, "table_name", &snake_case_headers),
+            build_create_table_sql(
+                &DatabaseEngine::MySQL,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
             "CREATE TABLE `table_name` (`header1` TEXT, `header2` TEXT)"
         );
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::Postgres, "table_name", &snake_case_headers),
-            "CREATE TABLE \"table_name\" (header1 TEXT, header2 TEXT)"
+            build_create_table_sql(
+                &DatabaseEngine::Postgres,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
+            "CREATE TABLE \"table_name\" (\"header1\" TEXT, \"header2\" TEXT)"
         );
 
         let snake_case_headers: Vec<String> = vec!["header1".into()];
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::SQLite, "table_name", &snake_case_headers),
-            "CREATE TABLE \"table_name\" (header1 TEXT)"
+            build_create_table_sql(
+                &DatabaseEngine::SQLite,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
+            "CREATE TABLE \"table_name\" (\"header1\" TEXT)"
         );
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::MySQL, "table_name", &snake_case_headers),
+            build_create_table_sql(
+                &DatabaseEngine::MySQL,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
             "CREATE TABLE `table_name` (`header1` TEXT)"
         );
         assert_eq!(
-            build_create_table_sql(&DatabaseEngine::Postgres, "table_name", &snake_case_headers),
-            "CREATE TABLE \"table_name\" (header1 TEXT)"
+            build_create_table_sql(
+                &DatabaseEngine::Postgres,
+                None,
+                "table_name",
+                &snake_case_headers
+            ),
+            "CREATE TABLE \"table_name\" (\"header1\" TEXT)"
         );
     }
 
@@ -290,8 +831,10 @@ mod test {
         const FINAL_TABLE_NAME: &str = "test_table";
 
         let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
-        let map_max_length: HashMap<&str, usize> =
-            snake_case_headers.iter().map(|h| (h.as_str(), 0)).collect();
+        let map_max_length: HashMap<&str, ColumnType> = snake_case_headers
+            .iter()
+            .map(|h| (h.as_str(), ColumnType::Varchar(0)))
+            .collect();
 
         let mut db_driver: HashMap<&DatabaseEngine, &str> = HashMap::new();
         db_driver.insert(
@@ -316,6 +859,7 @@ mod test {
         for (driver, expected) in db_driver {
             let result: String = build_create_with_fixed_size_sql(
                 driver,
+                None,
                 FINAL_TABLE_NAME,
                 &map_max_length,
                 &final_columns,
@@ -323,9 +867,9 @@ mod test {
             assert_eq!(result, expected);
         }
 
-        let map_max_length: HashMap<&str, usize> = snake_case_headers
+        let map_max_length: HashMap<&str, ColumnType> = snake_case_headers
             .iter()
-            .map(|h| (h.as_str(), 10))
+            .map(|h| (h.as_str(), ColumnType::Varchar(10)))
             .collect();
         let mut db_driver: HashMap<&DatabaseEngine, &str> = HashMap::new();
         db_driver.insert(
@@ -348,6 +892,7 @@ mod test {
         for (driver, expected) in db_driver {
             let result: String = build_create_with_fixed_size_sql(
                 driver,
+                None,
                 FINAL_TABLE_NAME,
                 &map_max_length,
                 &final_columns,
@@ -356,6 +901,146 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_create_statement_with_inferred_types() {
+        let final_columns: Vec<String> = vec![
+            "id".into(),
+            "price".into(),
+            "active".into(),
+            "created_at".into(),
+            "comment".into(),
+        ];
+        let map_column_type: HashMap<&str, ColumnType> = HashMap::from([
+            ("id", ColumnType::BigInt),
+            (
+                "price",
+                ColumnType::Decimal {
+                    precision: 8,
+                    scale: 2,
+                },
+            ),
+            ("active", ColumnType::Boolean),
+            ("created_at", ColumnType::Timestamp),
+            ("comment", ColumnType::Text),
+        ]);
+
+        assert_eq!(
+            build_create_with_fixed_size_sql(
+                &DatabaseEngine::Postgres,
+                None, "t",
+                &map_column_type,
+                &final_columns
+            ),
+            "CREATE TABLE \"t\" (\"id\" BIGINT, \"price\" DECIMAL(8,2), \"active\" BOOLEAN, \"created_at\" TIMESTAMP, \"comment\" TEXT);"
+        );
+        assert_eq!(
+            build_create_with_fixed_size_sql(
+                &DatabaseEngine::MySQL,
+                None, "t",
+                &map_column_type,
+                &final_columns
+            ),
+            "CREATE TABLE `t` (`id` BIGINT, `price` DECIMAL(8,2), `active` BOOLEAN, `created_at` DATETIME(6), `comment` TEXT);"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_column_type_sql() {
+        let test_cases: Vec<(&DatabaseEngine, ColumnType, &str)> = vec![
+            (&DatabaseEngine::MySQL, ColumnType::Integer, "INT"),
+            (&DatabaseEngine::Postgres, ColumnType::Integer, "INTEGER"),
+            (&DatabaseEngine::SQLite, ColumnType::Date, "DATE"),
+            (
+                &DatabaseEngine::MariaDB,
+                ColumnType::Timestamp,
+                "DATETIME(6)",
+            ),
+            (&DatabaseEngine::SQLite, ColumnType::Timestamp, "TIMESTAMP"),
+            (
+                &DatabaseEngine::Postgres,
+                ColumnType::Varchar(12),
+                "VARCHAR(12)",
+            ),
+        ];
+
+        for (driver, column_type, expected) in test_cases {
+            assert_eq!(build_column_type_sql(driver, &column_type), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_copy_table_sql() {
+        let final_columns: Vec<String> = vec!["id".into(), "name".into()];
+        let map_column_type: HashMap<&str, ColumnType> = HashMap::from([
+            ("id", ColumnType::Integer),
+            ("name", ColumnType::Varchar(10)),
+        ]);
+
+        assert_eq!(
+            build_copy_table_sql(
+                &DatabaseEngine::Postgres,
+                None, "t_temporary",
+                "t",
+                &map_column_type,
+                &final_columns
+            ),
+            "INSERT INTO \"t\" (\"id\", \"name\") SELECT CAST(NULLIF(\"id\", '') AS INTEGER), \"name\" FROM \"t_temporary\""
+        );
+        assert_eq!(
+            build_copy_table_sql(
+                &DatabaseEngine::MySQL,
+                None,
+                "t_temporary",
+                "t",
+                &map_column_type,
+                &final_columns
+            ),
+            "INSERT INTO `t` (`id`, `name`) SELECT NULLIF(`id`, ''), `name` FROM `t_temporary`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_alter_column_types_sql() {
+        let final_columns: Vec<String> = vec!["id".into(), "name".into(), "active".into()];
+        let map_column_type: HashMap<&str, ColumnType> = HashMap::from([
+            ("id", ColumnType::Integer),
+            ("name", ColumnType::Varchar(10)),
+            ("active", ColumnType::Boolean),
+        ]);
+
+        assert_eq!(
+            build_alter_column_types_sql(None, "t", &map_column_type, &final_columns),
+            Some("ALTER TABLE \"t\" ALTER COLUMN \"id\" TYPE INTEGER USING CAST(NULLIF(\"id\", '') AS INTEGER), ALTER COLUMN \"name\" TYPE VARCHAR(10), ALTER COLUMN \"active\" TYPE BOOLEAN USING CASE WHEN LOWER(\"active\") IN ('true', 't', 'yes', 'y') THEN TRUE WHEN LOWER(\"active\") IN ('false', 'f', 'no', 'n') THEN FALSE END".into())
+        );
+
+        let text_only: HashMap<&str, ColumnType> = HashMap::from([("id", ColumnType::Text)]);
+        assert_eq!(
+            build_alter_column_types_sql(None, "t", &text_only, &final_columns[..1]),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_add_column_sql() {
+        assert_eq!(
+            build_add_column_sql(
+                &DatabaseEngine::Postgres,
+                Some("public"),
+                "t",
+                "price",
+                &ColumnType::Decimal {
+                    precision: 6,
+                    scale: 2
+                }
+            ),
+            "ALTER TABLE \"public\".\"t\" ADD COLUMN \"price\" DECIMAL(6,2)"
+        );
+        assert_eq!(
+            build_add_column_sql(&DatabaseEngine::MySQL, None, "t", "note", &ColumnType::Text),
+            "ALTER TABLE `t` ADD COLUMN `note` TEXT"
+        );
+    }
+
     #[tokio::test]
     async fn test_build_query_all_tables() {
         let test_cases: Vec<(&DatabaseEngine, String)> = vec![
@@ -371,12 +1056,12 @@ mod test {
             ),
             (
                 &DatabaseEngine::Postgres,
-                "SELECT TABLE_NAME FROM information_schema.TABLES WHERE TABLE_SCHEMA = 'public';"
+                "SELECT TABLE_NAME FROM information_schema.TABLES WHERE TABLE_SCHEMA = 'test';"
                     .into(),
             ),
             (
                 &DatabaseEngine::SQLite,
-                "SELECT name FROM sqlite_master WHERE type='table';".into(),
+                "SELECT name FROM \"test\".sqlite_master WHERE type='table';".into(),
             ),
         ];
 
@@ -389,4 +1074,226 @@ mod test {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_build_query_all_schemas() {
+        assert_eq!(
+            build_query_all_schemas(&DatabaseEngine::SQLite),
+            "SELECT name AS schema_name FROM pragma_database_list ORDER BY seq;"
+        );
+        assert!(
+            build_query_all_schemas(&DatabaseEngine::Postgres).starts_with(
+                "SELECT schema_name::text AS schema_name FROM information_schema.schemata"
+            )
+        );
+        assert!(build_query_all_schemas(&DatabaseEngine::MySQL)
+            .starts_with("SELECT SCHEMA_NAME AS schema_name FROM information_schema.SCHEMATA"));
+    }
+
+    #[tokio::test]
+    async fn test_build_query_table_columns() {
+        assert_eq!(
+            build_query_table_columns(&DatabaseEngine::SQLite, "test", "test_table"),
+            "SELECT name AS column_name, type AS data_type, CASE WHEN \"notnull\" = 1 THEN 'NO' ELSE 'YES' END AS is_nullable, dflt_value AS column_default, NULL AS max_length FROM pragma_table_info('test_table', 'test');"
+        );
+        assert_eq!(
+            build_query_table_columns(&DatabaseEngine::MySQL, "test", "test_table"),
+            "SELECT COLUMN_NAME AS column_name, DATA_TYPE AS data_type, IS_NULLABLE AS is_nullable, COLUMN_DEFAULT AS column_default, CAST(CHARACTER_MAXIMUM_LENGTH AS SIGNED) AS max_length FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = 'test' AND TABLE_NAME = 'test_table' ORDER BY ORDINAL_POSITION;"
+        );
+        assert_eq!(
+            build_query_table_columns(&DatabaseEngine::Postgres, "public", "it's"),
+            "SELECT column_name::text AS column_name, data_type::text AS data_type, is_nullable::text AS is_nullable, column_default::text AS column_default, character_maximum_length::bigint AS max_length FROM information_schema.columns WHERE table_schema = 'public' AND table_name = 'it''s' ORDER BY ordinal_position;"
+        );
+        assert_eq!(
+            build_query_table_columns(&DatabaseEngine::Postgres, "staging", "sales.2024"),
+            "SELECT column_name::text AS column_name, data_type::text AS data_type, is_nullable::text AS is_nullable, column_default::text AS column_default, character_maximum_length::bigint AS max_length FROM information_schema.columns WHERE table_schema = 'staging' AND table_name = 'sales.2024' ORDER BY ordinal_position;"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_query_unique_keys() {
+        assert_eq!(
+            build_query_unique_keys(&DatabaseEngine::MySQL, "test", "it's"),
+            "SELECT INDEX_NAME AS index_name, COALESCE(COLUMN_NAME, '') AS column_name FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = 'test' AND TABLE_NAME = 'it''s' AND NON_UNIQUE = 0 ORDER BY INDEX_NAME, SEQ_IN_INDEX;"
+        );
+        assert_eq!(
+            build_query_unique_keys(&DatabaseEngine::Postgres, "public", "test_table"),
+            "SELECT i.relname::text AS index_name, a.attname::text AS column_name FROM pg_index x JOIN pg_class t ON t.oid = x.indrelid JOIN pg_namespace n ON n.oid = t.relnamespace JOIN pg_class i ON i.oid = x.indexrelid JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY((x.indkey::int2[])[0:x.indnkeyatts - 1]) WHERE x.indisunique AND x.indpred IS NULL AND x.indexprs IS NULL AND n.nspname = 'public' AND t.relname = 'test_table';"
+        );
+        assert_eq!(
+            build_query_unique_keys(&DatabaseEngine::SQLite, "main", "test_table"),
+            "SELECT il.name AS index_name, COALESCE(ii.name, '') AS column_name FROM pragma_index_list('test_table', 'main') AS il, pragma_index_info(il.name, 'main') AS ii WHERE il.\"unique\" = 1 AND il.partial = 0 UNION ALL SELECT 'rowid' AS index_name, name AS column_name FROM pragma_table_info('test_table', 'main') WHERE pk = 1 AND UPPER(type) = 'INTEGER' AND (SELECT COUNT(*) FROM pragma_table_info('test_table', 'main') WHERE pk > 0) = 1;"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_create_table_with_primary_key_sql() {
+        let headers: Vec<String> = vec!["id".into(), "name".into()];
+        let keys: Vec<String> = vec!["id".into()];
+        assert_eq!(
+            build_create_table_with_primary_key_sql(
+                &DatabaseEngine::SQLite,
+                None,
+                "t",
+                &headers,
+                &keys
+            ),
+            "CREATE TABLE \"t\" (\"id\" TEXT, \"name\" TEXT, PRIMARY KEY (\"id\"))"
+        );
+        assert_eq!(
+            build_create_table_with_primary_key_sql(
+                &DatabaseEngine::MariaDB,
+                None,
+                "t",
+                &headers,
+                &keys
+            ),
+            "CREATE TABLE `t` (`id` VARCHAR(255), `name` TEXT, PRIMARY KEY (`id`))"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_upsert_clause_sql() {
+        let columns: Vec<String> = vec!["id".into(), "name".into(), "city".into()];
+        let keys: Vec<String> = vec!["id".into()];
+
+        let test_cases: Vec<(&DatabaseEngine, &ConflictPolicy, &str)> = vec![
+            (
+                &DatabaseEngine::Postgres,
+                &ConflictPolicy::Update,
+                " ON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\", \"city\" = EXCLUDED.\"city\"",
+            ),
+            (
+                &DatabaseEngine::SQLite,
+                &ConflictPolicy::Ignore,
+                " ON CONFLICT (\"id\") DO NOTHING",
+            ),
+            (
+                &DatabaseEngine::MySQL,
+                &ConflictPolicy::Update,
+                " ON DUPLICATE KEY UPDATE `name` = VALUES(`name`), `city` = VALUES(`city`)",
+            ),
+            (
+                &DatabaseEngine::MariaDB,
+                &ConflictPolicy::Ignore,
+                " ON DUPLICATE KEY UPDATE `id` = `id`",
+            ),
+            (&DatabaseEngine::Postgres, &ConflictPolicy::Error, ""),
+            (&DatabaseEngine::MySQL, &ConflictPolicy::Error, ""),
+        ];
+
+        for (driver, policy, expected) in test_cases {
+            assert_eq!(
+                build_upsert_clause_sql(driver, &columns, &keys, policy),
+                expected,
+                "Failed for driver: {:?} and policy {:?}",
+                driver,
+                policy
+            );
+        }
+
+        // Nothing left to update when every column is part of the key
+        assert_eq!(
+            build_upsert_clause_sql(
+                &DatabaseEngine::Postgres,
+                &keys,
+                &keys,
+                &ConflictPolicy::Update
+            ),
+            " ON CONFLICT (\"id\") DO NOTHING"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_value_cast() {
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::Postgres, "integer"),
+            Some("integer".into())
+        );
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::Postgres, "timestamp without time zone"),
+            Some("timestamp without time zone".into())
+        );
+        assert_eq!(build_value_cast(&DatabaseEngine::Postgres, "text"), None);
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::Postgres, "character varying"),
+            None
+        );
+
+        // MySQL and SQLite only turn the empty strings of the non-text columns into NULL
+        for driver in [DatabaseEngine::MySQL, DatabaseEngine::MariaDB] {
+            assert_eq!(build_value_cast(&driver, "int"), Some("int".into()));
+            assert_eq!(build_value_cast(&driver, "decimal"), Some("decimal".into()));
+            assert_eq!(build_value_cast(&driver, "DATE"), Some("date".into()));
+            assert_eq!(build_value_cast(&driver, "varchar"), None);
+            assert_eq!(build_value_cast(&driver, "longtext"), None);
+            assert_eq!(build_value_cast(&driver, "enum"), None);
+        }
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::SQLite, "INTEGER"),
+            Some("integer".into())
+        );
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::SQLite, "DECIMAL(10, 2)"),
+            Some("decimal".into())
+        );
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::SQLite, "DATE"),
+            Some("date".into())
+        );
+        assert_eq!(build_value_cast(&DatabaseEngine::SQLite, "TEXT"), None);
+        assert_eq!(
+            build_value_cast(&DatabaseEngine::SQLite, "VARCHAR(255)"),
+            None
+        );
+        assert_eq!(build_value_cast(&DatabaseEngine::SQLite, ""), None);
+    }
+
+    #[tokio::test]
+    async fn test_build_copy_from_stdin_sql() {
+        let headers: Vec<String> = vec!["header1".into(), "header2".into()];
+        assert_eq!(
+            build_copy_from_stdin_sql(None, "table_name", &headers, &CsvDialect::default()),
+            "COPY \"table_name\" (\"header1\", \"header2\") FROM STDIN (FORMAT csv, HEADER true, DELIMITER ',', QUOTE '\"', FORCE_NOT_NULL (\"header1\", \"header2\"))"
+        );
+        let dialect: CsvDialect = CsvDialect {
+            separator: ';',
+            quote: '\'',
+            escape: None,
+            has_headers: true,
+        };
+        assert_eq!(
+            build_copy_from_stdin_sql(None, "table_name", &headers[..1], &dialect),
+            "COPY \"table_name\" (\"header1\") FROM STDIN (FORMAT csv, HEADER true, DELIMITER ';', QUOTE '''', FORCE_NOT_NULL (\"header1\"))"
+        );
+        let dialect: CsvDialect = CsvDialect {
+            escape: Some('\\'),
+            has_headers: false,
+            ..CsvDialect::default()
+        };
+        assert_eq!(
+            build_copy_from_stdin_sql(None, "table_name", &headers[..1], &dialect),
+            "COPY \"table_name\" (\"header1\") FROM STDIN (FORMAT csv, HEADER false, DELIMITER ',', QUOTE '\"', ESCAPE '\\', FORCE_NOT_NULL (\"header1\"))"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_shadow_table_sql() {
+        assert_eq!(
+            build_create_table_like_sql(None, "table_name_shadow", "table_name"),
+            "CREATE TABLE `table_name_shadow` LIKE `table_name`"
+        );
+        assert_eq!(
+            build_insert_from_table_sql(None, "table_name_shadow", "table_name"),
+            "INSERT INTO `table_name_shadow` SELECT * FROM `table_name`"
+        );
+        assert_eq!(
+            build_swap_table_sql(None, "table_name", "table_name_shadow", Some("table_name_replaced")),
+            "RENAME TABLE `table_name` TO `table_name_replaced`, `table_name_shadow` TO `table_name`"
+        );
+        assert_eq!(
+            build_swap_table_sql(None, "table_name", "table_name_shadow", None),
+            "RENAME TABLE `table_name_shadow` TO `table_name`"
+        );
+    }
 }
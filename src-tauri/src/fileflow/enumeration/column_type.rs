@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Boolean,
+    Integer,
+    BigInt,
+    Decimal { precision: usize, scale: usize },
+    Date,
+    Timestamp,
+    Varchar(usize),
+    Text,
+}
+
+impl ColumnType {
+    /// Returns true when the column stores the values as plain text.
+    pub const fn is_textual(&self) -> bool {
+        matches!(self, ColumnType::Varchar(_) | ColumnType::Text)
+    }
+
+    /// Returns true when the values of this type can be stored in a column of the given catalog type.
+    /// The type name is matched exactly once its parameters are removed, the length of a character column
+    /// being `max_length` or else its declared length. An untyped or unbounded text column accepts everything.
+    pub fn fits_in(&self, data_type: &str, max_length: Option<usize>) -> bool {
+        const UNBOUNDED_TEXT: [&str; 5] = ["text", "mediumtext", "longtext", "clob", "string"];
+        const BOUNDED_TEXT: [&str; 7] = [
+            "char",
+            "character",
+            "varchar",
+            "character varying",
+            "nchar",
+            "nvarchar",
+            "bpchar",
+        ];
+        const INTEGER: [&str; 5] = ["int", "integer", "int4", "serial", "serial4"];
+        const BIG_INTEGER: [&str; 4] = ["bigint", "int8", "bigserial", "serial8"];
+        const EXACT_NUMERIC: [&str; 2] = ["numeric", "decimal"];
+        const FLOATING: [&str; 6] = [
+            "real",
+            "float",
+            "float4",
+            "float8",
+            "double",
+            "double precision",
+        ];
+        const TIMESTAMP: [&str; 5] = [
+            "timestamp",
+            "timestamp without time zone",
+            "timestamp with time zone",
+            "timestamptz",
+            "datetime",
+        ];
+
+        let data_type: String = data_type.trim().to_lowercase();
+        let (name, parameters): (&str, Vec<usize>) = match data_type.split_once('(') {
+            Some((name, parameters)) => (
+                name.trim(),
+                parameters
+                    .trim_end_matches(')')
+                    .split(',')
+                    .filter_map(|parameter| parameter.trim().parse().ok())
+                    .collect(),
+            ),
+            None => (data_type.as_str(), Vec::new()),
+        };
+        let is = |names: &[&str]| names.contains(&name);
+        // The digits of a numeric column before and after its decimal point, unknown when it has no declared precision
+        let (integer_digits, column_scale): (Option<usize>, Option<usize>) = match parameters[..] {
+            [precision] => (Some(precision), Some(0)),
+            [precision, scale, ..] => (Some(precision.saturating_sub(scale)), Some(scale)),
+            [] => (None, None),
+        };
+
+        if name.is_empty() || is(&UNBOUNDED_TEXT) {
+            return true;
+        }
+        if is(&BOUNDED_TEXT) || name == "tinytext" {
+            let length: Option<usize> = match name {
+                "tinytext" => Some(255),
+                _ => max_length.or(parameters.first().copied()),
+            };
+            return match length {
+                Some(length) => self.get_max_chars().is_some_and(|chars| chars <= length),
+                None => true,
+            };
+        }
+
+        match self {
+            ColumnType::Boolean => is(&["bool", "boolean", "bit", "tinyint"]),
+            // i32 values don't fit in the 8, 16 or 24 bits integer columns
+            ColumnType::Integer => {
+                is(&INTEGER)
+                    || is(&BIG_INTEGER)
+                    || is(&["double", "double precision", "float8"])
+                    || (is(&EXACT_NUMERIC) && integer_digits.is_none_or(|digits| digits >= 10))
+            }
+            ColumnType::BigInt => {
+                is(&BIG_INTEGER)
+                    || (is(&EXACT_NUMERIC) && integer_digits.is_none_or(|digits| digits >= 19))
+            }
+            ColumnType::Decimal { precision, scale } => {
+                is(&FLOATING)
+                    || (is(&["money"]) && *scale <= 2)
+                    || (is(&EXACT_NUMERIC)
+                        && column_scale.is_none_or(|column_scale| *scale <= column_scale)
+                        && integer_digits.is_none_or(|digits| precision - scale <= digits))
+            }
+            ColumnType::Date => name == "date" || is(&TIMESTAMP),
+            ColumnType::Timestamp => is(&TIMESTAMP),
+            ColumnType::Varchar(_) | ColumnType::Text => false,
+        }
+    }
+
+    /// Longest text a value of this type is written as, unknown for the timestamps whose fraction and offset are free
+    const fn get_max_chars(&self) -> Option<usize> {
+        match self {
+            ColumnType::Boolean => Some(5),
+            ColumnType::Integer => Some(11),
+            ColumnType::BigInt => Some(20),
+            // A sign and a decimal point around the digits
+            ColumnType::Decimal { precision, .. } => Some(*precision + 2),
+            ColumnType::Date => Some(10),
+            ColumnType::Varchar(length) => Some(*length),
+            ColumnType::Timestamp | ColumnType::Text => None,
+        }
+    }
+}
@@ -8,3 +8,22 @@ pub enum DatabaseEngine {
     Postgres,
     SQLite,
 }
+
+impl DatabaseEngine {
+    /// Returns the maximum number of bind parameters allowed in a single statement.
+    pub const fn max_bind_parameters(&self) -> usize {
+        match self {
+            DatabaseEngine::MariaDB | DatabaseEngine::MySQL | DatabaseEngine::Postgres => 65_535,
+            DatabaseEngine::SQLite => 32_766,
+        }
+    }
+
+    /// Returns the maximum length in bytes of a table or column name, longer names are truncated or refused by the server.
+    pub const fn max_identifier_length(&self) -> usize {
+        match self {
+            DatabaseEngine::MariaDB | DatabaseEngine::MySQL => 64,
+            DatabaseEngine::Postgres => 63,
+            DatabaseEngine::SQLite => usize::MAX,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOperation {
+    Import,
+    Export,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOutcome {
+    Success,
+    Failed,
+    Cancelled,
+}
+
+impl HistoryOutcome {
+    /// Outcome of a command from its result, a cancelled job returns an error starting with `Cancelled`
+    pub fn from_error(error: &str) -> Self {
+        if error.starts_with("Cancelled") {
+            Self::Cancelled
+        } else {
+            Self::Failed
+        }
+    }
+}
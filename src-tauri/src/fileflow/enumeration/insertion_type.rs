@@ -6,4 +6,6 @@ pub enum InsertionType {
     #[default]
     Fast,
     Optimized,
+    Append,
+    Upsert,
 }
@@ -1,3 +1,10 @@
+pub mod column_type;
+pub mod conflict_policy;
 pub mod database_engine;
+pub mod history_operation;
+pub mod history_outcome;
 pub mod insertion_type;
+pub mod length_unit;
+pub mod optimized_strategy;
+pub mod reject_format;
 pub mod separator;
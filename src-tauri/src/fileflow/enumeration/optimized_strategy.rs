@@ -0,0 +1,35 @@
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use serde::{Deserialize, Serialize};
+
+/// How the optimized mode builds the typed final table from the csv file
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizedStrategy {
+    /// Load a text copy of the file in a temporary table, then copy it into the typed table
+    TemporaryTable,
+    /// Read the file once to infer the types, then a second time to insert into the typed table
+    TwoPass,
+    /// Load the file in a text table, then convert its columns in place (Postgres only)
+    AlterInPlace,
+}
+
+impl OptimizedStrategy {
+    /// Postgres converts the text columns in place after reading the file once,
+    /// the other engines can't change a column type in place and read the file twice instead
+    pub fn for_engine(db_driver: &DatabaseEngine) -> Self {
+        match db_driver {
+            DatabaseEngine::Postgres => Self::AlterInPlace,
+            DatabaseEngine::MariaDB | DatabaseEngine::MySQL | DatabaseEngine::SQLite => {
+                Self::TwoPass
+            }
+        }
+    }
+
+    /// Check that the engine can run the strategy
+    pub fn is_supported_by(&self, db_driver: &DatabaseEngine) -> bool {
+        match self {
+            Self::TemporaryTable | Self::TwoPass => true,
+            Self::AlterInPlace => *db_driver == DatabaseEngine::Postgres,
+        }
+    }
+}
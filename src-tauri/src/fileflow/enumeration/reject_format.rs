@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RejectFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+impl RejectFormat {
+    /// Returns the extension of the reject file.
+    pub const fn get_extension(&self) -> &str {
+        match self {
+            Self::Csv => "rejected.csv",
+            Self::Ndjson => "rejected.ndjson",
+        }
+    }
+}
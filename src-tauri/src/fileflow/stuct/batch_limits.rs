@@ -0,0 +1,81 @@
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use csv::StringRecord;
+
+/// Largest statement an engine accepts, a batch of records is split into statements that fit both limits
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BatchLimits {
+    pub max_parameters: usize,
+    pub max_bytes: usize,
+}
+
+impl BatchLimits {
+    /// Bytes counted for each bound value on top of its content, for its placeholder and its length header
+    const VALUE_OVERHEAD: usize = 16;
+
+    /// Postgres and SQLite have no packet limit low enough to matter, the bytes only bound the memory of a statement
+    const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+    /// Default `max_allowed_packet` of the oldest supported MySQL servers, used until the real value is known
+    const MYSQL_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+    pub const fn new(max_parameters: usize, max_bytes: usize) -> Self {
+        Self {
+            max_parameters,
+            max_bytes,
+        }
+    }
+
+    pub const fn for_engine(db_driver: &DatabaseEngine) -> Self {
+        let max_bytes: usize = match db_driver {
+            DatabaseEngine::MariaDB | DatabaseEngine::MySQL => Self::MYSQL_MAX_BYTES,
+            DatabaseEngine::Postgres | DatabaseEngine::SQLite => Self::DEFAULT_MAX_BYTES,
+        };
+        Self::new(db_driver.max_bind_parameters(), max_bytes)
+    }
+
+    /// Use the packet size reported by the server, keeping room for the statement itself
+    pub const fn with_max_packet(self, max_packet: usize) -> Self {
+        const STATEMENT_HEADER_BYTES: usize = 1024;
+        Self::new(
+            self.max_parameters,
+            max_packet.saturating_sub(STATEMENT_HEADER_BYTES),
+        )
+    }
+
+    /// Returns true when a statement binding this many parameters and bytes is larger than the engine accepts
+    pub const fn exceeds(&self, parameters: usize, bytes: usize) -> bool {
+        parameters > self.max_parameters || bytes > self.max_bytes
+    }
+
+    /// Estimated size of a record once bound to a statement
+    pub fn get_record_size(record: &StringRecord) -> usize {
+        record.as_slice().len() + record.len() * Self::VALUE_OVERHEAD
+    }
+
+    /// Split a batch into consecutive slices that each fit in a single statement, a record too large on its own gets its own slice
+    pub fn split<'a>(&self, batch: &'a [StringRecord]) -> Vec<&'a [StringRecord]> {
+        let mut statements: Vec<&'a [StringRecord]> = Vec::new();
+        let mut start: usize = 0;
+        let mut parameters: usize = 0;
+        let mut bytes: usize = 0;
+
+        for (i, record) in batch.iter().enumerate() {
+            let record_parameters: usize = record.len().max(1);
+            let record_bytes: usize = Self::get_record_size(record);
+
+            if i > start && self.exceeds(parameters + record_parameters, bytes + record_bytes) {
+                statements.push(&batch[start..i]);
+                start = i;
+                parameters = 0;
+                bytes = 0;
+            }
+            parameters += record_parameters;
+            bytes += record_bytes;
+        }
+
+        if start < batch.len() {
+            statements.push(&batch[start..]);
+        }
+        statements
+    }
+}
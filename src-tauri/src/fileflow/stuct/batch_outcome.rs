@@ -0,0 +1,32 @@
+use csv::StringRecord;
+use serde::Serialize;
+
+/// Rows inserted and rows refused by the database for one batch of an import
+#[derive(Default)]
+pub struct BatchOutcome {
+    pub inserted_count: u32,
+    pub rejected: Vec<(StringRecord, String)>,
+    pub failures: Vec<BatchFailure>,
+}
+
+/// Statement of a batch refused by the database, its rows were then retried one at a time
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BatchFailure {
+    /// Line of the file where the first row of the statement starts
+    pub first_line: Option<u64>,
+    pub row_count: usize,
+    pub error: String,
+}
+
+impl BatchFailure {
+    pub fn new(rows: &[StringRecord], error: &str) -> Self {
+        Self {
+            first_line: rows
+                .first()
+                .and_then(StringRecord::position)
+                .map(|position| position.line()),
+            row_count: rows.len(),
+            error: error.into(),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+
+/// Narrowest type a value, or a whole column, can be stored as.
+/// The order of the variants follows the widening of the numeric and temporal families.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+enum ValueKind {
+    #[default]
+    Empty,
+    Boolean,
+    Integer,
+    BigInt,
+    Decimal,
+    Date,
+    Timestamp,
+    String,
+}
+
+impl ValueKind {
+    /// Least upper bound of two kinds in the type lattice
+    fn join(self, other: ValueKind) -> ValueKind {
+        use ValueKind::*;
+        match (self, other) {
+            (Empty, kind) | (kind, Empty) => kind,
+            (a, b) if a == b => a,
+            (Integer | BigInt | Decimal, Integer | BigInt | Decimal) => {
+                if self as u8 > other as u8 {
+                    self
+                } else {
+                    other
+                }
+            }
+            (Date | Timestamp, Date | Timestamp) => Timestamp,
+            _ => String,
+        }
+    }
+}
+
+/// Statistics gathered over the values of a column to infer the type of the final table column.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnProfile {
+    pub max_chars: usize,
+    pub max_bytes: usize,
+    kind: ValueKind,
+    integer_digits: usize,
+    scale: usize,
+}
+
+impl ColumnProfile {
+    const MAX_DECIMAL_PRECISION: usize = 65;
+
+    /// Widen the profile so that the given value fits in it
+    pub fn observe(&mut self, value: &str) {
+        self.max_bytes = self.max_bytes.max(value.len());
+        // A value has at most as many characters as bytes, they are only counted when it may be the widest
+        if value.len() > self.max_chars {
+            self.max_chars = self.max_chars.max(value.chars().count());
+        }
+
+        if self.kind == ValueKind::String || value.is_empty() {
+            return;
+        }
+
+        let kind: ValueKind = self.classify(value);
+        self.kind = self.kind.join(kind);
+
+        if self.kind == ValueKind::Decimal
+            && self.integer_digits + self.scale > Self::MAX_DECIMAL_PRECISION
+        {
+            self.kind = ValueKind::String;
+        }
+    }
+
+    /// True while every value observed is empty, the column then has no type of its own
+    pub fn is_empty(&self) -> bool {
+        self.kind == ValueKind::Empty
+    }
+
+    /// Get the type of the column from all the values observed so far, a text column being sized as the engine requires
+    pub fn column_type(&self, sizing: &VarcharSizing) -> ColumnType {
+        match self.kind {
+            ValueKind::Boolean => ColumnType::Boolean,
+            ValueKind::Integer => ColumnType::Integer,
+            ValueKind::BigInt => ColumnType::BigInt,
+            ValueKind::Decimal => ColumnType::Decimal {
+                precision: (self.integer_digits + self.scale).max(1),
+                scale: self.scale,
+            },
+            ValueKind::Date => ColumnType::Date,
+            ValueKind::Timestamp => ColumnType::Timestamp,
+            ValueKind::Empty | ValueKind::String => {
+                sizing.column_type(self.max_chars, self.max_bytes)
+            }
+        }
+    }
+
+    /// Find the narrowest kind of a non-empty value, recording the digits of numeric values
+    fn classify(&mut self, value: &str) -> ValueKind {
+        if let Some((integer_digits, scale)) = Self::parse_number(value) {
+            self.integer_digits = self.integer_digits.max(integer_digits);
+            self.scale = self.scale.max(scale);
+            return if scale > 0 {
+                ValueKind::Decimal
+            } else if value.parse::<i32>().is_ok() {
+                ValueKind::Integer
+            } else if value.parse::<i64>().is_ok() {
+                ValueKind::BigInt
+            } else {
+                ValueKind::Decimal
+            };
+        }
+
+        if Self::is_boolean(value) {
+            ValueKind::Boolean
+        } else if Self::is_date(value) {
+            ValueKind::Date
+        } else if Self::is_timestamp(value) {
+            ValueKind::Timestamp
+        } else {
+            ValueKind::String
+        }
+    }
+
+    /// Parse a plain decimal number and return its integer and fractional digit counts.
+    /// Leading zeros are refused so that codes such as zip codes stay text.
+    fn parse_number(value: &str) -> Option<(usize, usize)> {
+        let unsigned: &str = value.strip_prefix('-').unwrap_or(value);
+        let (integer_part, fraction_part): (&str, &str) = match unsigned.split_once('.') {
+            Some((integer_part, fraction_part)) if !fraction_part.is_empty() => {
+                (integer_part, fraction_part)
+            }
+            Some(_) => return None,
+            None => (unsigned, ""),
+        };
+
+        if integer_part.is_empty()
+            || !integer_part.bytes().all(|b| b.is_ascii_digit())
+            || !fraction_part.bytes().all(|b| b.is_ascii_digit())
+            || (integer_part.len() > 1 && integer_part.starts_with('0'))
+        {
+            return None;
+        }
+
+        Some((integer_part.len(), fraction_part.len()))
+    }
+
+    /// Check if a value is a boolean literal understood by every engine
+    fn is_boolean(value: &str) -> bool {
+        Self::parse_boolean(value).is_some()
+    }
+
+    /// Value of a boolean literal, `None` when the value is not one
+    pub fn parse_boolean(value: &str) -> Option<bool> {
+        const TRUE_VALUES: [&str; 4] = ["true", "t", "yes", "y"];
+        const FALSE_VALUES: [&str; 4] = ["false", "f", "no", "n"];
+        let matches = |literals: &[&str]| {
+            literals
+                .iter()
+                .any(|literal| literal.eq_ignore_ascii_case(value))
+        };
+
+        if matches(&TRUE_VALUES) {
+            Some(true)
+        } else if matches(&FALSE_VALUES) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Check if a value is an ISO 8601 date (YYYY-MM-DD)
+    fn is_date(value: &str) -> bool {
+        let bytes: &[u8] = value.as_bytes();
+        if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+            return false;
+        }
+
+        let (Some(year), Some(month), Some(day)) = (
+            Self::parse_digits(&value[0..4]),
+            Self::parse_digits(&value[5..7]),
+            Self::parse_digits(&value[8..10]),
+        ) else {
+            return false;
+        };
+
+        let is_leap_year: bool = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days_in_month: u32 = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year => 29,
+            2 => 28,
+            _ => return false,
+        };
+
+        year > 0 && (1..=days_in_month).contains(&day)
+    }
+
+    /// Check if a value is an ISO 8601 timestamp without time zone (YYYY-MM-DD HH:MM:SS[.ffffff])
+    fn is_timestamp(value: &str) -> bool {
+        if value.len() < 19 || !value.is_char_boundary(10) || !value.is_char_boundary(19) {
+            return false;
+        }
+
+        let (date, time): (&str, &str) = value.split_at(10);
+        if !Self::is_date(date) {
+            return false;
+        }
+
+        let bytes: &[u8] = time.as_bytes();
+        if !(bytes[0] == b' ' || bytes[0] == b'T') || bytes[3] != b':' || bytes[6] != b':' {
+            return false;
+        }
+
+        let (Some(hour), Some(minute), Some(second)) = (
+            Self::parse_digits(&time[1..3]),
+            Self::parse_digits(&time[4..6]),
+            Self::parse_digits(&time[7..9]),
+        ) else {
+            return false;
+        };
+
+        let fraction_is_valid: bool = match time[9..].strip_prefix('.') {
+            Some(fraction) => {
+                (1..=6).contains(&fraction.len()) && Self::parse_digits(fraction).is_some()
+            }
+            None => time.len() == 9,
+        };
+
+        hour < 24 && minute < 60 && second < 60 && fraction_is_valid
+    }
+
+    /// Parse a string made only of ASCII digits
+    fn parse_digits(value: &str) -> Option<u32> {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        value.parse().ok()
+    }
+}
@@ -0,0 +1,42 @@
+use csv::ReaderBuilder;
+use serde::Serialize;
+
+/// Format of a csv file: its separator, its quote, how quotes are escaped inside a field and whether it starts with a header row
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct CsvDialect {
+    pub separator: char,
+    pub quote: char,
+    /// `None` when quotes are escaped by doubling them, the csv standard
+    pub escape: Option<char>,
+    pub has_headers: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            separator: ',',
+            quote: '"',
+            escape: None,
+            has_headers: true,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Reader builder parsing the records of a file written in this dialect.
+    /// A record with another width than the header row is reported as malformed, so that it is rejected
+    pub fn get_reader_builder(&self) -> ReaderBuilder {
+        let mut builder: ReaderBuilder = ReaderBuilder::new();
+        builder
+            .flexible(false)
+            .delimiter(u8::try_from(self.separator).unwrap_or(b','))
+            .quote(u8::try_from(self.quote).unwrap_or(b'"'))
+            .has_headers(self.has_headers);
+        if let Some(escape) = self.escape {
+            builder
+                .escape(Some(u8::try_from(escape).unwrap_or(b'\\')))
+                .double_quote(false);
+        }
+        builder
+    }
+}
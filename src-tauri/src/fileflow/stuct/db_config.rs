@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Clone, Serialize)]
 pub struct DbConfig {
+    /// Name the config was saved under, empty for a connection typed by hand
+    #[serde(default)]
+    pub config_name: String,
     pub db_driver: DatabaseEngine,
     pub db_host: String,
     pub port: String,
@@ -11,3 +14,28 @@ pub struct DbConfig {
     pub db_name: String,
     pub sqlite_file_path: String,
 }
+
+impl DbConfig {
+    /// Schema the unqualified table names belong to
+    pub fn get_default_schema(&self) -> &str {
+        match self.db_driver {
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB => &self.db_name,
+            DatabaseEngine::Postgres => "public",
+            DatabaseEngine::SQLite => "main",
+        }
+    }
+
+    /// Name of the connection written in the history, never holding its password
+    pub fn get_connection_name(&self) -> String {
+        if !self.config_name.is_empty() {
+            return self.config_name.clone();
+        }
+        match self.db_driver {
+            DatabaseEngine::SQLite => self.sqlite_file_path.clone(),
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB | DatabaseEngine::Postgres => format!(
+                "{}@{}:{}/{}",
+                self.username, self.db_host, self.port, self.db_name
+            ),
+        }
+    }
+}
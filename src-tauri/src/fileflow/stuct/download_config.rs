@@ -6,4 +6,9 @@ pub struct DownloadConfig {
     pub table_name_list: Vec<String>,
     pub location: String,
     pub separator: SeparatorType,
+    /// Schema of the exported tables, the default one of the connection when `None`
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Id the caller cancels the export with, unique among the running jobs
+    pub job_id: String,
 }
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// Outcome of an export, returned to the frontend and to any script calling the command
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ExportReport {
+    pub tables_requested: usize,
+    pub tables: Vec<TableExport>,
+    /// Error of each table that could not be exported
+    pub failures: Vec<String>,
+    pub duration_ms: u64,
+    /// Human-readable sentence for display
+    pub summary: String,
+}
+
+/// File written for one exported table
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TableExport {
+    pub table_name: String,
+    pub file_path: String,
+    pub rows_exported: u64,
+    pub bytes_written: u64,
+}
@@ -0,0 +1,126 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+
+/// Part of a file between its preamble lines and its footer lines, read and seeked as if it was the whole file
+pub struct FileSection {
+    file: File,
+    start: u64,
+    start_line: u64,
+    end: u64,
+    position: u64,
+}
+
+impl FileSection {
+    /// Open the file without its first `preamble_lines` lines and its last `footer_lines` lines
+    pub fn open(file_path: &str, preamble_lines: usize, footer_lines: usize) -> io::Result<Self> {
+        let mut file: File = File::open(file_path)?;
+        let len: u64 = file.metadata()?.len();
+
+        let (start, start_line): (u64, u64) = Self::find_preamble_end(&mut file, preamble_lines)?;
+        let end: u64 = Self::find_footer_start(&mut file, len, footer_lines)?.max(start);
+
+        file.seek(SeekFrom::Start(start))?;
+        Ok(Self {
+            file,
+            start,
+            start_line,
+            end,
+            position: 0,
+        })
+    }
+
+    pub const fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Byte offset of the section in the file
+    pub const fn get_start(&self) -> u64 {
+        self.start
+    }
+
+    /// Number of file lines before the section, the preamble lines that were found
+    pub const fn get_start_line(&self) -> u64 {
+        self.start_line
+    }
+
+    /// Byte offset of the line following the preamble and the number of lines skipped
+    fn find_preamble_end(file: &mut File, preamble_lines: usize) -> io::Result<(u64, u64)> {
+        let mut reader: BufReader<&mut File> = BufReader::new(file);
+        let mut line: Vec<u8> = Vec::new();
+        let mut offset: u64 = 0;
+        let mut lines: u64 = 0;
+        for _ in 0..preamble_lines {
+            line.clear();
+            let read: usize = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            offset += read as u64;
+            lines += 1;
+        }
+        Ok((offset, lines))
+    }
+
+    /// Byte offset of the first footer line, the file is read backwards by chunks so that a large file is not read entirely
+    fn find_footer_start(file: &mut File, len: u64, footer_lines: usize) -> io::Result<u64> {
+        const CHUNK_SIZE: u64 = 64 * 1024;
+
+        if footer_lines == 0 {
+            return Ok(len);
+        }
+
+        let mut remaining: usize = footer_lines;
+        let mut buffer: Vec<u8> = vec![0; CHUNK_SIZE as usize];
+        let mut chunk_end: u64 = len;
+        while chunk_end > 0 {
+            let chunk_start: u64 = chunk_end.saturating_sub(CHUNK_SIZE);
+            let chunk: &mut [u8] = &mut buffer[..(chunk_end - chunk_start) as usize];
+            file.seek(SeekFrom::Start(chunk_start))?;
+            file.read_exact(chunk)?;
+
+            for (index, byte) in chunk.iter().enumerate().rev() {
+                let offset: u64 = chunk_start + index as u64;
+                // The terminator of the last line does not start another line
+                if *byte != b'\n' || offset == len - 1 {
+                    continue;
+                }
+                remaining -= 1;
+                if remaining == 0 {
+                    return Ok(offset + 1);
+                }
+            }
+            chunk_end = chunk_start;
+        }
+        Ok(0)
+    }
+}
+
+impl Read for FileSection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left: u64 = self.len().saturating_sub(self.position);
+        let limit: usize = buf.len().min(usize::try_from(left).unwrap_or(usize::MAX));
+        let read: usize = self.file.read(&mut buf[..limit])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for FileSection {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target: i128 = match pos {
+            SeekFrom::Start(offset) => i128::from(offset),
+            SeekFrom::End(offset) => i128::from(self.len()) + i128::from(offset),
+            SeekFrom::Current(offset) => i128::from(self.position) + i128::from(offset),
+        };
+        let position: u64 = u64::try_from(target).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek before the start of the section",
+            )
+        })?;
+
+        self.file.seek(SeekFrom::Start(self.start + position))?;
+        self.position = position;
+        Ok(position)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::fileflow::enumeration::history_operation::HistoryOperation;
+use crate::fileflow::enumeration::history_outcome::HistoryOutcome;
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::stuct::export_report::ExportReport;
+use crate::fileflow::stuct::import_report::ImportReport;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One import or export run, appended to the history log once the command returns
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    /// Start of the run, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub operation: HistoryOperation,
+    /// Csv file of an import, directory of an export
+    pub file_path: String,
+    /// Fingerprint of the imported file, telling whether the same file was loaded again
+    pub file_hash: Option<String>,
+    /// Saved config name of the connection, or its address without the password
+    pub connection: String,
+    pub tables: Vec<String>,
+    pub mode: Option<InsertionType>,
+    pub rows: u64,
+    pub rows_rejected: u64,
+    pub duration_ms: u64,
+    pub outcome: HistoryOutcome,
+    /// Summary of the report, or the error of a failed run
+    pub message: String,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        operation: HistoryOperation,
+        file_path: &str,
+        file_hash: Option<String>,
+        connection: &str,
+        tables: Vec<String>,
+        mode: Option<InsertionType>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            operation,
+            file_path: file_path.into(),
+            file_hash,
+            connection: connection.into(),
+            tables,
+            mode,
+            rows: 0,
+            rows_rejected: 0,
+            duration_ms: 0,
+            outcome: HistoryOutcome::Success,
+            message: String::new(),
+        }
+    }
+
+    pub fn with_import_result(
+        mut self,
+        result: &Result<ImportReport, String>,
+        elapsed: Duration,
+    ) -> Self {
+        match result {
+            Ok(report) => {
+                // The mode that ran, an import whose table already existed may have appended to it
+                self.mode = Some(report.mode.clone());
+                self.rows = report.rows_inserted;
+                self.rows_rejected = report.rows_rejected;
+                self.duration_ms = report.duration_ms;
+                self.message = report.summary.clone();
+            }
+            Err(err) => self.set_error(err, elapsed),
+        }
+        self
+    }
+
+    pub fn with_export_result(
+        mut self,
+        result: &Result<ExportReport, String>,
+        elapsed: Duration,
+    ) -> Self {
+        match result {
+            Ok(report) => {
+                self.rows = report.tables.iter().map(|table| table.rows_exported).sum();
+                self.duration_ms = report.duration_ms;
+                self.message = report.summary.clone();
+                if report.tables.is_empty() {
+                    self.outcome = HistoryOutcome::Failed;
+                }
+            }
+            Err(err) => self.set_error(err, elapsed),
+        }
+        self
+    }
+
+    fn set_error(&mut self, error: &str, elapsed: Duration) {
+        self.outcome = HistoryOutcome::from_error(error);
+        self.duration_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        self.message = error.into();
+    }
+}
@@ -0,0 +1,47 @@
+use crate::fileflow::enumeration::history_operation::HistoryOperation;
+use crate::fileflow::enumeration::history_outcome::HistoryOutcome;
+use crate::fileflow::stuct::history_entry::HistoryEntry;
+use serde::Deserialize;
+
+/// Criteria of the history entries to list, every criterion left to `None` matches all entries
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub operation: Option<HistoryOperation>,
+    #[serde(default)]
+    pub outcome: Option<HistoryOutcome>,
+    /// Name of a table of the run, compared without case
+    #[serde(default)]
+    pub table_name: Option<String>,
+    /// Part of the file path of the run
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Earliest and latest start of the run, in seconds since the Unix epoch
+    #[serde(default)]
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub until: Option<u64>,
+    /// Maximum number of entries returned, the most recent ones first
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.operation
+            .is_none_or(|operation| operation == entry.operation)
+            && self.outcome.is_none_or(|outcome| outcome == entry.outcome)
+            && self.table_name.as_ref().is_none_or(|table_name| {
+                entry
+                    .tables
+                    .iter()
+                    .any(|table| table.eq_ignore_ascii_case(table_name))
+            })
+            && self
+                .file_path
+                .as_ref()
+                .is_none_or(|file_path| entry.file_path.contains(file_path.as_str()))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+    }
+}
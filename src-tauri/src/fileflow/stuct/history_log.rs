@@ -0,0 +1,86 @@
+use crate::fileflow::stuct::history_entry::HistoryEntry;
+use crate::fileflow::stuct::history_filter::HistoryFilter;
+use crate::fileflow::utils::constants::HISTORY_FILE;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// Append-only JSON lines file holding one entry per import or export run
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// History log of the application, stored in its data directory
+    pub fn open(app: &AppHandle) -> Result<Self, String> {
+        let data_dir: PathBuf = app
+            .path()
+            .app_data_dir()
+            .map_err(|err| format!("Failed to find the app data directory: {err}"))?;
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|err| format!("Failed to create the app data directory: {err}"))?;
+        Ok(Self::new(data_dir.join(HISTORY_FILE)))
+    }
+
+    /// Append the entry to the history of the application, a failure is logged without failing the run
+    pub fn record(app: &AppHandle, entry: &HistoryEntry) {
+        if let Err(err) = Self::open(app).and_then(|history| {
+            history
+                .append(entry)
+                .map_err(|err| format!("Failed to write the history: {err}"))
+        }) {
+            eprintln!("Error: {err}");
+        }
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> io::Result<()> {
+        let mut file: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut line: String = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+    }
+
+    /// Entries matching the filter, the most recent first. A line that can't be parsed is skipped
+    pub fn list(&self, filter: &HistoryFilter) -> io::Result<Vec<HistoryEntry>> {
+        let file: File = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line: String = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<HistoryEntry>(&line) {
+                Ok(entry) if filter.matches(&entry) => entries.push(entry),
+                Ok(_) => {}
+                Err(err) => eprintln!("Error: Skipping an unreadable history entry: {err}"),
+            }
+        }
+
+        entries.reverse();
+        if let Some(limit) = filter.limit {
+            entries.truncate(limit);
+        }
+        Ok(entries)
+    }
+
+    /// Remove every entry of the history
+    pub fn clear(&self) -> io::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::fileflow::utils::csv_utils::get_file_fingerprint;
+use csv::{Position, Reader, StringRecord};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek};
+use std::path::Path;
+
+/// Position of the last committed batch of an import, saved next to the csv file so that an interrupted import can be resumed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ImportCheckpoint {
+    pub file_path: String,
+    pub fingerprint: String,
+    pub table_name: String,
+    pub byte_offset: u64,
+    pub line: u64,
+    pub record: u64,
+    pub rows_committed: u64,
+}
+
+impl ImportCheckpoint {
+    pub fn new(file_path: &str, fingerprint: &str, table_name: &str) -> Self {
+        Self {
+            file_path: file_path.into(),
+            fingerprint: fingerprint.into(),
+            table_name: table_name.into(),
+            byte_offset: 0,
+            line: 0,
+            record: 0,
+            rows_committed: 0,
+        }
+    }
+
+    pub fn get_checkpoint_path(file_path: &str) -> String {
+        Path::new(file_path)
+            .with_extension("checkpoint.json")
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Load the checkpoint saved for the given csv file, if any
+    pub fn load(file_path: &str) -> Option<Self> {
+        let file: File = File::open(Self::get_checkpoint_path(file_path)).ok()?;
+        serde_json::from_reader(file).ok()
+    }
+
+    /// Remove the checkpoint saved for the given csv file, once its import is over
+    pub fn remove(file_path: &str) {
+        let checkpoint_path: String = Self::get_checkpoint_path(file_path);
+        if Path::new(&checkpoint_path).exists() {
+            if let Err(err) = std::fs::remove_file(&checkpoint_path) {
+                eprintln!("Error: Failed to remove the import checkpoint: {err}");
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file: File = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(Self::get_checkpoint_path(&self.file_path))?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
+    /// Check that the checkpoint resumes the import of its file into the given table, and return the fingerprint of the file.
+    /// The whole content is digested again, so that a change anywhere in the file refuses the checkpoint
+    pub fn validate(&self, table_name: &str) -> Result<String, String> {
+        if self.table_name != table_name {
+            return Err(format!(
+                "The checkpoint of {} was saved for the table {}",
+                self.file_path, self.table_name
+            ));
+        }
+
+        let fingerprint: String = get_file_fingerprint(&self.file_path)
+            .map_err(|err| format!("Failed to read file: {err}"))?;
+        if fingerprint != self.fingerprint {
+            return Err(format!(
+                "{} changed since the checkpoint was saved, the import can't be resumed",
+                self.file_path
+            ));
+        }
+        Ok(fingerprint)
+    }
+
+    /// A checkpoint with an offset has at least one committed batch to resume from
+    pub const fn is_started(&self) -> bool {
+        self.byte_offset > 0
+    }
+
+    /// Record the position of the last committed record
+    pub fn set_position(&mut self, position: &Position, rows_committed: u64) {
+        self.byte_offset = position.byte();
+        self.line = position.line();
+        self.record = position.record();
+        self.rows_committed = rows_committed;
+    }
+
+    /// Move the reader right after the last committed record, the following records are the ones left to import
+    pub fn seek_reader(&self, reader: &mut Reader<impl Read + Seek>) -> Result<(), String> {
+        let mut position: Position = Position::new();
+        position
+            .set_byte(self.byte_offset)
+            .set_line(self.line)
+            .set_record(self.record);
+        reader
+            .seek(position)
+            .map_err(|err| format!("Failed to seek to the checkpoint: {err}"))?;
+
+        let mut last_committed: StringRecord = StringRecord::new();
+        reader
+            .read_record(&mut last_committed)
+            .map_err(|err| format!("Failed to read the last committed record: {err}"))?;
+        Ok(())
+    }
+}
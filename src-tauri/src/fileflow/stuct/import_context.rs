@@ -0,0 +1,243 @@
+use crate::fileflow::enumeration::reject_format::RejectFormat;
+use crate::fileflow::stuct::batch_outcome::BatchFailure;
+use crate::fileflow::stuct::cancel_token::CancelToken;
+use crate::fileflow::stuct::file_section::FileSection;
+use crate::fileflow::stuct::import_checkpoint::ImportCheckpoint;
+use crate::fileflow::stuct::progress::{Progress, ProgressListener};
+use crate::fileflow::stuct::reject_writer::RejectWriter;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+use crate::fileflow::utils::csv_utils::get_file_size;
+use csv::{Position, StringRecord};
+use std::time::Instant;
+
+/// State shared by the insert functions while a csv file is imported
+pub struct ImportContext {
+    pub rejects: RejectWriter,
+    progress_listener: Option<Box<dyn ProgressListener>>,
+    cancel_token: CancelToken,
+    checkpoint: Option<ImportCheckpoint>,
+    resumed: bool,
+    resumed_rows: u64,
+    resumed_bytes: u64,
+    rows_read: u64,
+    workers: usize,
+    varchar_sizing: VarcharSizing,
+    ddl: Vec<String>,
+    batch_failures: Vec<BatchFailure>,
+    warnings: Vec<String>,
+    total_bytes: u64,
+    bytes_read: u64,
+    start: Instant,
+}
+
+impl ImportContext {
+    pub fn new(file_path: &str, separator: char, reject_format: RejectFormat) -> Self {
+        Self {
+            rejects: RejectWriter::new(file_path, separator, reject_format),
+            progress_listener: None,
+            cancel_token: CancelToken::default(),
+            checkpoint: None,
+            resumed: false,
+            resumed_rows: 0,
+            resumed_bytes: 0,
+            rows_read: 0,
+            workers: 1,
+            varchar_sizing: VarcharSizing::default(),
+            ddl: Vec::new(),
+            batch_failures: Vec::new(),
+            warnings: Vec::new(),
+            total_bytes: get_file_size(file_path).unwrap_or_default(),
+            bytes_read: 0,
+            start: Instant::now(),
+        }
+    }
+
+    /// Send the progress of the import to the given listener after each batch
+    pub fn with_progress_listener(mut self, listener: Box<dyn ProgressListener>) -> Self {
+        self.progress_listener = Some(listener);
+        self
+    }
+
+    /// Stop the import at the next batch once the token is cancelled
+    pub fn with_cancel_token(mut self, cancel_token: CancelToken) -> Self {
+        self.cancel_token = cancel_token;
+        self
+    }
+
+    /// Read the records from a section of the file: the progress is measured against the section length
+    /// and the rejected records are located in the whole file
+    pub fn with_file_section(mut self, section: &FileSection) -> Self {
+        self.total_bytes = section.len();
+        self.rejects
+            .set_section_offset(section.get_start(), section.get_start_line());
+        self
+    }
+
+    /// Insert the batches with several workers at the same time, on the engines that allow concurrent writers
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub const fn get_workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Size the text columns of the optimized mode with the settings of the import, the generic default otherwise
+    pub const fn with_varchar_sizing(mut self, varchar_sizing: VarcharSizing) -> Self {
+        self.varchar_sizing = varchar_sizing;
+        self
+    }
+
+    pub const fn get_varchar_sizing(&self) -> &VarcharSizing {
+        &self.varchar_sizing
+    }
+
+    /// Count a record produced by the reader, a record that could not be parsed is rejected and not returned
+    pub fn accept_record(
+        &mut self,
+        result: csv::Result<StringRecord>,
+    ) -> Result<Option<StringRecord>, String> {
+        self.rows_read += 1;
+        match result {
+            Ok(record) => Ok(Some(record)),
+            Err(err) => {
+                self.rejects.reject_malformed(&err)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Count the rows read by an import that does not go through the csv reader, such as a COPY
+    pub fn add_rows_read(&mut self, rows: u64) {
+        self.rows_read += rows;
+    }
+
+    /// Rows of the file read by this run, the ones skipped by a resumed import excluded
+    pub const fn get_rows_read(&self) -> u64 {
+        self.rows_read
+    }
+
+    /// Keep a statement that created or converted the final table, for the import report
+    pub fn record_ddl(&mut self, statement: &str) {
+        self.ddl.push(statement.into());
+    }
+
+    pub fn get_ddl(&self) -> &[String] {
+        &self.ddl
+    }
+
+    pub fn record_batch_failure(&mut self, failure: BatchFailure) {
+        self.batch_failures.push(failure);
+    }
+
+    pub fn get_batch_failures(&self) -> &[BatchFailure] {
+        &self.batch_failures
+    }
+
+    /// Keep a problem that did not stop the import, such as a fallback to a slower insert method
+    pub fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn get_warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Bytes of the file read by this run, from the checkpoint of a resumed import to the furthest record reported
+    pub const fn get_bytes_processed(&self) -> u64 {
+        self.bytes_read.saturating_sub(self.resumed_bytes)
+    }
+
+    /// Save the given checkpoint after each committed batch, a checkpoint that already has an offset resumes a previous import
+    pub fn with_checkpoint(mut self, checkpoint: ImportCheckpoint) -> Self {
+        if checkpoint.is_started() {
+            self.resumed = true;
+            self.resumed_rows = checkpoint.rows_committed;
+            self.resumed_bytes = checkpoint.byte_offset;
+            self.rejects.append_to_existing();
+        }
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// A resumed import continues filling the table left by the interrupted one
+    pub const fn is_resumed(&self) -> bool {
+        self.resumed
+    }
+
+    pub const fn get_resumed_rows(&self) -> u64 {
+        self.resumed_rows
+    }
+
+    /// Persist the position of the last record of a committed batch, a failure only costs the ability to resume
+    pub fn save_checkpoint(&mut self, batch: &[StringRecord], rows_committed: u32) {
+        if let Some(position) = batch.last().and_then(StringRecord::position) {
+            self.save_checkpoint_at(position, rows_committed);
+        }
+    }
+
+    /// Persist the position of the furthest record read once everything before it is committed,
+    /// for the imports whose batches don't keep the records in file order
+    pub fn save_checkpoint_at(&mut self, position: &Position, rows_committed: u32) {
+        let rows_committed: u64 = self.resumed_rows + u64::from(rows_committed);
+        let Some(checkpoint) = self.checkpoint.as_mut() else {
+            return;
+        };
+
+        checkpoint.set_position(position, rows_committed);
+        if let Err(err) = checkpoint.save() {
+            eprintln!("Error: Failed to save the import checkpoint: {err}");
+        }
+    }
+
+    /// Remove the saved checkpoint once the import no longer needs to be resumed
+    pub fn remove_checkpoint(&self) {
+        if let Some(checkpoint) = &self.checkpoint {
+            ImportCheckpoint::remove(&checkpoint.file_path);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.is_cancelled()
+    }
+
+    /// Checked between batches, a cancelled import stops with an error so that the caller can clean up
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err("Import cancelled".into());
+        }
+        Ok(())
+    }
+
+    /// Report the progress once a batch is processed, the bytes read are given by the position of its furthest record
+    pub fn report_batch(&mut self, table_name: &str, batch: &[StringRecord], rows_processed: u32) {
+        let bytes_read: u64 = batch
+            .iter()
+            .filter_map(StringRecord::position)
+            .map(Position::byte)
+            .max()
+            .unwrap_or_default();
+        self.report(table_name, bytes_read, u64::from(rows_processed));
+    }
+
+    /// Report that the whole file has been read
+    pub fn report_completed(&mut self, table_name: &str, rows_processed: u64) {
+        self.report(table_name, self.total_bytes, rows_processed);
+    }
+
+    /// Report the progress of an import that does not read the records itself, such as a COPY
+    pub fn report(&mut self, table_name: &str, bytes_read: u64, rows_processed: u64) {
+        self.bytes_read = self.bytes_read.max(bytes_read);
+        if let Some(listener) = &self.progress_listener {
+            listener.on_progress(&Progress::new(
+                table_name,
+                bytes_read,
+                self.total_bytes,
+                self.resumed_rows + rows_processed,
+                self.rejects.get_rejected_count(),
+                self.start.elapsed(),
+            ));
+        }
+    }
+}
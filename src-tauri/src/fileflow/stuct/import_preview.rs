@@ -0,0 +1,18 @@
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
+use serde::Serialize;
+
+/// What an import would do, computed from the file alone without touching the database
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ImportPreview {
+    pub table_name: String,
+    pub mode: InsertionType,
+    pub dialect: CsvDialect,
+    pub column_names: Vec<String>,
+    /// First records of the file, as they would be bound to the INSERT statement
+    pub rows: Vec<Vec<String>>,
+    /// Statements creating the tables of the import. The upsert one only runs when the table does not exist yet
+    pub ddl: Vec<String>,
+    /// COPY statement of a fast import into Postgres, otherwise the prefix of the INSERT statements, the values of each batch following it
+    pub insert_sql: String,
+}
@@ -0,0 +1,31 @@
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::stuct::batch_outcome::BatchFailure;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
+use crate::fileflow::stuct::schema_drift::SchemaDrift;
+use serde::Serialize;
+
+/// Outcome of a csv import, returned to the frontend and to any script calling the command
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ImportReport {
+    pub table_name: String,
+    pub mode: InsertionType,
+    /// Records read from the file by this run, inserted or rejected
+    pub rows_read: u64,
+    pub rows_inserted: u64,
+    pub rows_rejected: u64,
+    /// Rows committed by the interrupted import a resumed one continued, not read again
+    pub rows_resumed: u64,
+    /// Bytes of the file read by this run, a resumed import starting from its checkpoint
+    pub bytes_processed: u64,
+    pub duration_ms: u64,
+    pub batch_failures: Vec<BatchFailure>,
+    pub dialect: CsvDialect,
+    /// Statements that created or converted the final table, empty when the rows went into an existing table
+    pub ddl: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Differences found with the existing table when the new columns of the file were added to it
+    pub schema_drift: Option<SchemaDrift>,
+    pub reject_path: Option<String>,
+    /// Human-readable sentence for display
+    pub summary: String,
+}
@@ -1,11 +1,104 @@
+use crate::fileflow::database::sql_builder::get_qualified_table_name;
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
 use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::enumeration::length_unit::LengthUnit;
+use crate::fileflow::enumeration::optimized_strategy::OptimizedStrategy;
+use crate::fileflow::enumeration::reject_format::RejectFormat;
+use crate::fileflow::stuct::file_section::FileSection;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
 use serde::Deserialize;
+use std::io;
 
 #[derive(Deserialize)]
 pub struct InsertConfig {
     pub file_path: String,
     pub table_name: String,
+    /// Schema of the target table, the default one of the connection when `None`
+    #[serde(default)]
+    pub schema: Option<String>,
     pub mode: InsertionType,
     pub db_driver: DatabaseEngine,
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// Load MySQL and MariaDB tables in fast mode with statements as large as the server accepts, on a session of its own
+    #[serde(default)]
+    pub bulk_load: bool,
+    #[serde(default)]
+    pub transactional: bool,
+    #[serde(default)]
+    pub reject_format: RejectFormat,
+    /// Id the caller cancels the import with, unique among the running jobs
+    pub job_id: String,
+    #[serde(default)]
+    pub workers: usize,
+    /// Forces whether the file starts with a header row, detected from the file when `None`
+    #[serde(default)]
+    pub has_headers: Option<bool>,
+    /// Names given to the columns instead of the header row, or of `column_1..n` for a headerless file
+    #[serde(default)]
+    pub column_names: Vec<String>,
+    #[serde(default)]
+    pub preamble_lines: usize,
+    #[serde(default)]
+    pub footer_lines: usize,
+    /// Unit, threshold and padding of the VARCHAR columns of the optimized mode, the defaults of the engine when `None`
+    #[serde(default)]
+    pub varchar_length_unit: Option<LengthUnit>,
+    #[serde(default)]
+    pub varchar_max_length: Option<usize>,
+    #[serde(default)]
+    pub varchar_padding: Option<usize>,
+    /// How the optimized mode builds its typed table, the default of the engine when `None`
+    #[serde(default)]
+    pub optimized_strategy: Option<OptimizedStrategy>,
+    /// Re-importing into an existing table adds the new columns of the file to it and appends the rows,
+    /// instead of dropping the table
+    #[serde(default)]
+    pub add_new_columns: bool,
+}
+
+impl InsertConfig {
+    /// Name of the target table qualified by its schema, if any
+    pub fn get_qualified_table_name(&self) -> String {
+        get_qualified_table_name(self.schema.as_deref(), &self.table_name)
+    }
+
+    pub fn get_varchar_sizing(&self) -> VarcharSizing {
+        VarcharSizing::default().with_overrides(
+            self.varchar_length_unit,
+            self.varchar_max_length,
+            self.varchar_padding,
+        )
+    }
+
+    pub fn get_optimized_strategy(&self) -> OptimizedStrategy {
+        self.optimized_strategy
+            .unwrap_or_else(|| OptimizedStrategy::for_engine(&self.db_driver))
+    }
+
+    /// Open the part of the csv file holding its header row and its records, without the preamble and footer lines
+    pub fn open_file(&self) -> io::Result<FileSection> {
+        FileSection::open(&self.file_path, self.preamble_lines, self.footer_lines)
+    }
+
+    /// Only the imports that keep their committed batches after an interruption can be resumed from a checkpoint
+    pub fn is_resumable(&self) -> bool {
+        !self.transactional
+            && matches!(
+                self.mode,
+                InsertionType::Fast | InsertionType::Append | InsertionType::Upsert
+            )
+    }
+
+    /// A resumable import saves checkpoints, except the COPY into a new Postgres table, which has no batch to resume from.
+    /// New columns may turn the import into an append to the existing table, which is checkpointed
+    pub fn is_checkpointed(&self) -> bool {
+        self.is_resumable()
+            && !(self.mode == InsertionType::Fast
+                && self.db_driver == DatabaseEngine::Postgres
+                && !self.add_new_columns)
+    }
 }
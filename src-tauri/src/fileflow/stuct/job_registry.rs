@@ -0,0 +1,61 @@
+use crate::fileflow::stuct::cancel_token::CancelToken;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Cancel tokens of the running jobs, kept apart from the database state which stays locked while a job runs
+#[derive(Default)]
+pub struct JobRegistry(Mutex<HashMap<String, CancelToken>>);
+
+/// Registration of a running job, removed from the registry once dropped
+pub struct JobGuard<'a> {
+    registry: &'a JobRegistry,
+    job_id: String,
+    pub cancel_token: CancelToken,
+}
+
+impl JobRegistry {
+    /// Register a job, the returned guard holds the token to check between batches.
+    /// An id that is empty or already running is refused, the jobs sharing it could not be told apart
+    pub fn register(&self, job_id: &str) -> Result<JobGuard<'_>, String> {
+        if job_id.is_empty() {
+            return Err("A job id is required".into());
+        }
+
+        let cancel_token: CancelToken = CancelToken::default();
+        let mut jobs = self.0.lock().expect("Job registry lock poisoned");
+        if jobs.contains_key(job_id) {
+            return Err(format!("Job '{job_id}' is already running"));
+        }
+        jobs.insert(job_id.into(), cancel_token.clone());
+
+        Ok(JobGuard {
+            registry: self,
+            job_id: job_id.into(),
+            cancel_token,
+        })
+    }
+
+    /// Ask a running job to stop, returns false if no job has this id
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self
+            .0
+            .lock()
+            .expect("Job registry lock poisoned")
+            .get(job_id)
+        {
+            Some(cancel_token) => {
+                cancel_token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for JobGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = self.registry.0.lock() {
+            jobs.remove(&self.job_id);
+        }
+    }
+}
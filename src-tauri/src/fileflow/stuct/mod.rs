@@ -1,6 +1,26 @@
+pub mod batch_limits;
+pub mod batch_outcome;
+pub mod cancel_token;
+pub mod column_profile;
 pub mod combo_item;
+pub mod csv_dialect;
 pub mod db_config;
 pub mod download_config;
+pub mod export_report;
+pub mod file_section;
+pub mod history_entry;
+pub mod history_filter;
+pub mod history_log;
+pub mod import_checkpoint;
+pub mod import_context;
+pub mod import_preview;
+pub mod import_report;
 pub mod insert_config;
+pub mod job_registry;
+pub mod progress;
+pub mod reject_writer;
 pub mod save_config;
+pub mod schema_drift;
 pub mod string_formater;
+pub mod table_column;
+pub mod varchar_sizing;
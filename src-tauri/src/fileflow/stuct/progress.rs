@@ -0,0 +1,77 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Progress of an import or an export, reported after each batch
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Progress {
+    pub table_name: String,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    pub rows_processed: u64,
+    pub rows_rejected: u64,
+    pub estimated_remaining_seconds: Option<u64>,
+}
+
+impl Progress {
+    /// Build the progress of an import, the remaining time is extrapolated from the bytes already read
+    pub fn new(
+        table_name: &str,
+        bytes_read: u64,
+        total_bytes: u64,
+        rows_processed: u64,
+        rows_rejected: u64,
+        elapsed: Duration,
+    ) -> Self {
+        let bytes_read: u64 = bytes_read.min(total_bytes);
+        let estimated_remaining_seconds: Option<u64> = if bytes_read == 0 {
+            None
+        } else {
+            let remaining_bytes: f64 = (total_bytes - bytes_read) as f64;
+            Some((elapsed.as_secs_f64() * remaining_bytes / bytes_read as f64).round() as u64)
+        };
+
+        Self {
+            table_name: table_name.into(),
+            bytes_read,
+            total_bytes,
+            rows_processed,
+            rows_rejected,
+            estimated_remaining_seconds,
+        }
+    }
+}
+
+/// Receives the progress of a running import or export, implemented for closures so non-UI callers can follow it too
+pub trait ProgressListener: Send + Sync {
+    fn on_progress(&self, progress: &Progress);
+}
+
+impl<F> ProgressListener for F
+where
+    F: Fn(&Progress) + Send + Sync,
+{
+    fn on_progress(&self, progress: &Progress) {
+        self(progress);
+    }
+}
+
+/// Forwards the progress to the UI as a Tauri event
+pub struct EventProgressListener {
+    app: AppHandle,
+    event: &'static str,
+}
+
+impl EventProgressListener {
+    pub const fn new(app: AppHandle, event: &'static str) -> Self {
+        Self { app, event }
+    }
+}
+
+impl ProgressListener for EventProgressListener {
+    fn on_progress(&self, progress: &Progress) {
+        if let Err(err) = self.app.emit(self.event, progress) {
+            eprintln!("Error: Failed to emit progress event: {err}");
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::fileflow::enumeration::reject_format::RejectFormat;
+use csv::{StringRecord, WriterBuilder};
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Writes the records that could not be imported next to the source file, with their line number and the reason.
+/// The reject file is only created once the first record is rejected.
+pub struct RejectWriter {
+    source_path: String,
+    separator: u8,
+    format: RejectFormat,
+    reject_path: String,
+    writer: Option<BufWriter<File>>,
+    rejected_count: u64,
+    append: bool,
+    byte_offset: u64,
+    line_offset: u64,
+}
+
+impl RejectWriter {
+    pub fn new(source_path: &str, separator: char, format: RejectFormat) -> Self {
+        let reject_path: String = Path::new(source_path)
+            .with_extension(format.get_extension())
+            .to_string_lossy()
+            .into_owned();
+
+        Self {
+            source_path: source_path.into(),
+            separator: u8::try_from(separator).unwrap_or(b','),
+            format,
+            reject_path,
+            writer: None,
+            rejected_count: 0,
+            append: false,
+            byte_offset: 0,
+            line_offset: 0,
+        }
+    }
+
+    /// Keep the records rejected by a previous run of the same import, used when an import is resumed
+    pub fn append_to_existing(&mut self) {
+        self.append = true;
+    }
+
+    /// Report the positions of records read from a section of the source file as positions in the whole file
+    pub fn set_section_offset(&mut self, byte_offset: u64, line_offset: u64) {
+        self.byte_offset = byte_offset;
+        self.line_offset = line_offset;
+    }
+
+    pub fn get_reject_path(&self) -> &str {
+        &self.reject_path
+    }
+
+    pub const fn get_rejected_count(&self) -> u64 {
+        self.rejected_count
+    }
+
+    /// Reject a record refused by the database, the raw text is the record written back with the source separator
+    pub fn reject_record(&mut self, record: &StringRecord, reason: &str) -> Result<(), String> {
+        let line: u64 = record
+            .position()
+            .map_or(0, |position| position.line() + self.line_offset);
+
+        let mut raw_writer = WriterBuilder::new()
+            .delimiter(self.separator)
+            .from_writer(Vec::new());
+        raw_writer
+            .write_record(record)
+            .map_err(|err| format!("Failed to write rejected record: {err}"))?;
+        let raw: Vec<u8> = raw_writer
+            .into_inner()
+            .map_err(|err| format!("Failed to write rejected record: {err}"))?;
+
+        self.write(line, String::from_utf8_lossy(&raw).trim_end(), reason)
+    }
+
+    /// Reject a record the csv reader could not parse, the raw text is the source line the record starts on
+    pub fn reject_malformed(&mut self, error: &csv::Error) -> Result<(), String> {
+        let (line, raw): (u64, String) = match error.position() {
+            Some(position) => (
+                position.line() + self.line_offset,
+                self.read_source_line(position.byte() + self.byte_offset)?,
+            ),
+            None => (0, String::new()),
+        };
+
+        self.write(line, &raw, &error.to_string())
+    }
+
+    /// Flush the reject file if any record was rejected
+    pub fn flush(&mut self) -> Result<(), String> {
+        match self.writer.as_mut() {
+            Some(writer) => writer
+                .flush()
+                .map_err(|err| format!("Failed to write reject file: {err}")),
+            None => Ok(()),
+        }
+    }
+
+    fn read_source_line(&self, byte_offset: u64) -> Result<String, String> {
+        let mut file: File = File::open(&self.source_path)
+            .map_err(|err| format!("Failed to open source file: {err}"))?;
+        file.seek(SeekFrom::Start(byte_offset))
+            .map_err(|err| format!("Failed to read source file: {err}"))?;
+
+        let mut line: Vec<u8> = Vec::new();
+        BufReader::new(file)
+            .read_until(b'\n', &mut line)
+            .map_err(|err| format!("Failed to read source file: {err}"))?;
+
+        Ok(String::from_utf8_lossy(&line)
+            .trim_end_matches(['\r', '\n'])
+            .to_string())
+    }
+
+    fn write(&mut self, line: u64, raw: &str, reason: &str) -> Result<(), String> {
+        let entry: Vec<u8> = match self.format {
+            RejectFormat::Csv => {
+                let mut entry_writer = WriterBuilder::new().from_writer(Vec::new());
+                entry_writer
+                    .write_record([line.to_string().as_str(), reason, raw])
+                    .map_err(|err| format!("Failed to write reject file: {err}"))?;
+                entry_writer
+                    .into_inner()
+                    .map_err(|err| format!("Failed to write reject file: {err}"))?
+            }
+            RejectFormat::Ndjson => {
+                format!("{}\n", json!({ "line": line, "error": reason, "raw": raw })).into_bytes()
+            }
+        };
+
+        if self.writer.is_none() {
+            let existing: bool = self.append && Path::new(&self.reject_path).exists();
+            let file: File = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .append(existing)
+                .truncate(!existing)
+                .open(&self.reject_path)
+                .map_err(|err| format!("Failed to create reject file: {err}"))?;
+            let mut writer: BufWriter<File> = BufWriter::new(file);
+            if self.format == RejectFormat::Csv && !existing {
+                writer
+                    .write_all(b"line,error,raw\n")
+                    .map_err(|err| format!("Failed to write reject file: {err}"))?;
+            }
+            self.writer = Some(writer);
+        }
+
+        if let Some(writer) = self.writer.as_mut() {
+            writer
+                .write_all(&entry)
+                .map_err(|err| format!("Failed to write reject file: {err}"))?;
+        }
+        self.rejected_count += 1;
+
+        Ok(())
+    }
+}
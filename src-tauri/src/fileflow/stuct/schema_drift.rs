@@ -0,0 +1,155 @@
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::stuct::string_formater::StringFormatter;
+use crate::fileflow::stuct::table_column::TableColumn;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Differences between the columns of a csv file and those of the existing table it is imported into
+#[derive(Debug, Clone, Serialize, PartialEq, Default)]
+pub struct SchemaDrift {
+    pub table_name: String,
+    pub table_exists: bool,
+    /// Columns of the csv file the table does not have
+    pub added: Vec<String>,
+    /// Columns of the table the csv file does not have
+    pub missing: Vec<String>,
+    /// Pairs of an added and a missing column whose names are close enough to be the same column renamed
+    pub renamed: Vec<RenamedColumn>,
+    /// Columns whose inferred csv type can't be stored in the type of the table column
+    pub incompatible: Vec<IncompatibleColumn>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RenamedColumn {
+    pub table_column: String,
+    pub csv_column: String,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct IncompatibleColumn {
+    pub table_column: String,
+    pub csv_column: String,
+    pub table_type: String,
+    pub csv_type: ColumnType,
+}
+
+impl SchemaDrift {
+    const RENAME_SIMILARITY: f64 = 0.7;
+
+    /// Compare the formatted column names of the csv file and their inferred types with the columns of the table.
+    /// A column without a type in `column_types`, such as an all-empty one, is never incompatible
+    pub fn compare(
+        table_name: &str,
+        csv_columns: &[String],
+        column_types: &HashMap<&str, ColumnType>,
+        table_columns: &[TableColumn],
+        db_driver: &DatabaseEngine,
+    ) -> Self {
+        let mut drift: SchemaDrift = SchemaDrift {
+            table_name: table_name.into(),
+            table_exists: !table_columns.is_empty(),
+            ..Default::default()
+        };
+        if !drift.table_exists {
+            return drift;
+        }
+
+        // Table columns are compared under the same name formatting and deduplication as the csv headers
+        let table_names: Vec<String> = StringFormatter::get_formated_column_names(
+            &table_columns
+                .iter()
+                .map(|column| column.name.clone())
+                .collect::<Vec<String>>(),
+            db_driver,
+        );
+
+        let mut matched: Vec<(&str, usize)> = Vec::with_capacity(csv_columns.len());
+        let mut added: Vec<&str> = Vec::new();
+        for csv_column in csv_columns.iter() {
+            match table_names.iter().position(|name| name == csv_column) {
+                Some(index) => matched.push((csv_column, index)),
+                None => added.push(csv_column),
+            }
+        }
+        let mut missing: Vec<usize> = (0..table_columns.len())
+            .filter(|index| {
+                !matched
+                    .iter()
+                    .any(|(_, matched_index)| matched_index == index)
+            })
+            .collect();
+
+        // The closest pairs are taken first, each column being renamed at most once
+        let mut candidates: Vec<(f64, &str, usize)> = Vec::new();
+        for csv_column in added.iter() {
+            for index in missing.iter() {
+                let similarity: f64 = StringFormatter::similarity(csv_column, &table_names[*index]);
+                if similarity >= Self::RENAME_SIMILARITY {
+                    candidates.push((similarity, csv_column, *index));
+                }
+            }
+        }
+        candidates.sort_by(|left, right| right.0.total_cmp(&left.0));
+        for (similarity, csv_column, index) in candidates {
+            if !added.contains(&csv_column) || !missing.contains(&index) {
+                continue;
+            }
+            added.retain(|column| *column != csv_column);
+            missing.retain(|missing_index| *missing_index != index);
+            drift.renamed.push(RenamedColumn {
+                table_column: table_columns[index].name.clone(),
+                csv_column: csv_column.into(),
+                similarity,
+            });
+            matched.push((csv_column, index));
+        }
+
+        for (csv_column, index) in matched {
+            let table_column: &TableColumn = &table_columns[index];
+            let Some(csv_type) = column_types.get(csv_column) else {
+                continue;
+            };
+            if !csv_type.fits_in(&table_column.data_type, table_column.max_length) {
+                drift.incompatible.push(IncompatibleColumn {
+                    table_column: table_column.name.clone(),
+                    csv_column: csv_column.into(),
+                    table_type: table_column.data_type.clone(),
+                    csv_type: csv_type.clone(),
+                });
+            }
+        }
+
+        drift.added = added.into_iter().map(String::from).collect();
+        drift.missing = missing
+            .into_iter()
+            .map(|index| table_columns[index].name.clone())
+            .collect();
+        drift
+    }
+
+    /// One human-readable warning per difference other than the added columns
+    pub fn get_warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = Vec::new();
+        for column in self.missing.iter() {
+            warnings.push(format!(
+                "Column '{column}' of {} is not in the csv file",
+                self.table_name
+            ));
+        }
+        for column in self.renamed.iter() {
+            warnings.push(format!(
+                "Column '{}' of the csv file may be column '{}' of {} renamed",
+                column.csv_column, column.table_column, self.table_name
+            ));
+        }
+        for column in self.incompatible.iter() {
+            warnings.push(format!(
+                "Column '{}' of the csv file holds {:?} values that don't fit in the {} column '{}'",
+                column.csv_column, column.csv_type, column.table_type, column.table_column
+            ));
+        }
+        warnings
+    }
+}
@@ -1,39 +1,116 @@
-use csv::StringRecord;
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::utils::constants::SQL_RESERVED_WORDS;
+use std::collections::HashSet;
 
 pub struct StringFormatter;
 
 impl StringFormatter {
     /// This function is used to generate the column names for a CSV file.
-    pub fn get_formated_column_names(headers: &[String]) -> Vec<String> {
+    /// Names colliding once formatted are suffixed with their occurrence (`name`, `name_2`)
+    pub fn get_formated_column_names(
+        headers: &[String],
+        db_driver: &DatabaseEngine,
+    ) -> Vec<String> {
         const COLUMN_PREFIX: &str = "column_";
         let mut safe_headers: Vec<String> = Vec::with_capacity(headers.len());
+        let mut used_names: HashSet<String> = HashSet::with_capacity(headers.len());
 
         for (index, item) in headers.iter().enumerate() {
-            let trimmed_column_name: &str = item.trim();
-            if trimmed_column_name.is_empty() {
-                safe_headers.push(format!("{COLUMN_PREFIX}{}", index + 1));
-            } else {
-                safe_headers.push(Self::sanitize_column(trimmed_column_name));
+            let column_name: String = match Self::format_column_name(item, db_driver) {
+                name if name.is_empty() => format!("{COLUMN_PREFIX}{}", index + 1),
+                name => name,
+            };
+
+            let mut unique_name: String = column_name.clone();
+            let mut occurrence: usize = 2;
+            while used_names.contains(&unique_name) {
+                let suffix: String = format!("_{occurrence}");
+                let max_length: usize = db_driver.max_identifier_length() - suffix.len();
+                unique_name = format!("{}{suffix}", Self::truncate(&column_name, max_length));
+                occurrence += 1;
             }
+
+            used_names.insert(unique_name.clone());
+            safe_headers.push(unique_name);
         }
         safe_headers
     }
 
-    /// Sanitize a value for safe insertion into the database
-    pub fn sanitize_value(value: &str) -> String {
-        let mut sanitized: String = String::with_capacity(value.len());
+    /// Formatted names of the key columns of a csv file, each looked up among its headers first so that a collision keeps its suffix.
+    /// A key can also be given by its formatted name
+    pub fn get_formated_key_names(
+        keys: &[String],
+        headers: &[String],
+        column_names: &[String],
+        db_driver: &DatabaseEngine,
+    ) -> Result<Vec<String>, String> {
+        keys.iter()
+            .map(|key| {
+                if let Some(index) = headers.iter().position(|header| header == key) {
+                    return Ok(column_names[index].clone());
+                }
+                if column_names.contains(key) {
+                    return Ok(key.clone());
+                }
+                let formatted: String = Self::format_column_name(key, db_driver);
+                if column_names.contains(&formatted) {
+                    return Ok(formatted);
+                }
+                Err(format!("Key column '{key}' does not exist in the csv file"))
+            })
+            .collect()
+    }
 
-        for c in value.trim().chars() {
-            match c {
-                '\'' => sanitized.push_str("''"),   // Escape single quotes
-                '\\' => sanitized.push_str("\\\\"), // Escape backslashes
-                '\"' | '\0' => {}                   // Remove double quotes and null characters
-                '\r' | '\n' => sanitized.push(' '), // Normalize newlines
-                _ => sanitized.push(c),
+    /// Sanitize a column name and rewrite it into a valid unquoted identifier of the engine:
+    /// a reserved word gets a trailing underscore, a leading digit a leading one, and a long name is truncated
+    pub fn format_column_name(value: &str, db_driver: &DatabaseEngine) -> String {
+        let mut name: String = Self::sanitize_column(value);
+        if name.is_empty() {
+            return name;
+        }
+
+        if SQL_RESERVED_WORDS.contains(&name.as_str()) {
+            name.push('_');
+        }
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        Self::truncate(&name, db_driver.max_identifier_length()).into()
+    }
+
+    /// Similarity of two names between 0 and 1, from their Levenshtein distance relative to the longest one
+    pub fn similarity(left: &str, right: &str) -> f64 {
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+        let longest: usize = left.len().max(right.len());
+        if longest == 0 {
+            return 1.0;
+        }
+
+        // Only the previous row of the distance matrix is kept
+        let mut previous: Vec<usize> = (0..=right.len()).collect();
+        let mut current: Vec<usize> = vec![0; right.len() + 1];
+        for (i, left_char) in left.iter().enumerate() {
+            current[0] = i + 1;
+            for (j, right_char) in right.iter().enumerate() {
+                let substitution: usize = previous[j] + usize::from(left_char != right_char);
+                current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
             }
+            std::mem::swap(&mut previous, &mut current);
         }
+        1.0 - previous[right.len()] as f64 / longest as f64
+    }
 
-        sanitized
+    /// Longest prefix of the value fitting in `max_length` bytes without splitting a character
+    fn truncate(value: &str, max_length: usize) -> &str {
+        if value.len() <= max_length {
+            return value;
+        }
+        let mut end: usize = max_length;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        &value[..end]
     }
 
     /// Sanitize a column name for safe insertion into the database
@@ -62,13 +139,4 @@ impl StringFormatter {
 
         result
     }
-
-    /// Escape values for SQL insert statement to avoid SQL injection attacks and other issues with special characters in values.
-    pub fn escaped_record(values: StringRecord) -> String {
-        let vec: Vec<String> = values
-            .iter()
-            .map(|v| format!("'{}'", Self::sanitize_value(v)))
-            .collect();
-        vec.join(", ")
-    }
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Description of a column of an existing table, as reported by the database catalog.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: String,
+    /// Maximum number of characters of a character column, when the catalog reports it
+    pub max_length: Option<usize>,
+    pub is_nullable: bool,
+    pub has_default: bool,
+}
+
+impl TableColumn {
+    /// A column is required when it can neither be NULL nor be filled by a default value.
+    pub const fn is_required(&self) -> bool {
+        !self.is_nullable && !self.has_default
+    }
+}
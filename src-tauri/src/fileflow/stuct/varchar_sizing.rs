@@ -0,0 +1,52 @@
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::length_unit::LengthUnit;
+
+/// How the width of a text column is measured and sized, a column wider than `max_length` is stored as TEXT
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct VarcharSizing {
+    pub unit: LengthUnit,
+    pub max_length: usize,
+    pub padding: usize,
+}
+
+/// VARCHAR up to 255 characters on every engine: MySQL and MariaDB rows hold at most 65,535 bytes,
+/// which a few dozen wider `utf8mb4` columns would exceed
+impl Default for VarcharSizing {
+    fn default() -> Self {
+        Self {
+            unit: LengthUnit::Characters,
+            max_length: 255,
+            padding: 1,
+        }
+    }
+}
+
+impl VarcharSizing {
+    /// Replace the defaults by the given settings, if any
+    pub fn with_overrides(
+        self,
+        unit: Option<LengthUnit>,
+        max_length: Option<usize>,
+        padding: Option<usize>,
+    ) -> Self {
+        Self {
+            unit: unit.unwrap_or(self.unit),
+            max_length: max_length.unwrap_or(self.max_length),
+            padding: padding.unwrap_or(self.padding),
+        }
+    }
+
+    /// Type of a text column from the widest value it holds, in characters and in bytes
+    pub fn column_type(&self, max_chars: usize, max_bytes: usize) -> ColumnType {
+        let width: usize = match self.unit {
+            LengthUnit::Characters => max_chars,
+            LengthUnit::Bytes => max_bytes,
+        } + self.padding;
+
+        if width <= self.max_length {
+            ColumnType::Varchar(width.max(1))
+        } else {
+            ColumnType::Text
+        }
+    }
+}
@@ -1,24 +1,270 @@
-use std::fs::File;
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
+use crate::fileflow::stuct::string_formater::StringFormatter;
+use csv::{ByteRecord, Position, Reader, StringRecord};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 
-/// This function is used to detect the separator from a string.
-pub fn find_separator(line: &str) -> Result<char, String> {
-    const POSSIBLE_SEPARATORS: [char; 6] = [',', ';', '\t', '|', ' ', '\0'];
-    for sep in POSSIBLE_SEPARATORS.iter() {
-        if line.contains(*sep) {
-            return Ok(*sep);
+const POSSIBLE_SEPARATORS: [char; 6] = [',', ';', '\t', '|', ' ', '\0'];
+
+/// Detect the dialect of a csv file, see `sniff_dialect_from_reader`
+#[cfg(test)]
+pub fn sniff_dialect(file_path: &str) -> io::Result<CsvDialect> {
+    sniff_dialect_from_reader(File::open(file_path)?)
+}
+
+/// Detect the dialect of csv data from a sample of its first lines.
+/// Each candidate separator is scored by how consistent the number of fields per record is over the sample
+pub fn sniff_dialect_from_reader(source: impl Read) -> io::Result<CsvDialect> {
+    const SAMPLE_LINES: usize = 100;
+    const MAX_SAMPLE_BYTES: usize = 1 << 20;
+
+    let mut reader: BufReader<_> = BufReader::new(source);
+    let mut sample: String = String::new();
+    for _ in 0..SAMPLE_LINES {
+        if reader.read_line(&mut sample)? == 0 {
+            break;
+        }
+    }
+    if sample.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "File is empty"));
+    }
+
+    let quote: char = find_quote(&sample);
+    let escape: Option<char> = find_escape(&sample, quote);
+
+    // The sample is extended to the end of a quoted field spanning its last line, so that its last record is whole
+    while ends_inside_quotes(&sample, quote, escape) && sample.len() < MAX_SAMPLE_BYTES {
+        if reader.read_line(&mut sample)? == 0 {
+            break;
         }
     }
-    Err("Could not detect a valid separator".into())
+    let separator: char = find_separator(&sample, quote, escape).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Could not detect a valid separator",
+        )
+    })?;
+
+    let mut dialect: CsvDialect = CsvDialect {
+        separator,
+        quote,
+        escape,
+        has_headers: false,
+    };
+    let records: Vec<StringRecord> = parse_sample(&sample, &dialect);
+    dialect.has_headers = has_header_row(&records);
+
+    Ok(dialect)
+}
+
+/// Parse the sample without any header, records of different widths are kept to be scored
+fn parse_sample(sample: &str, dialect: &CsvDialect) -> Vec<StringRecord> {
+    dialect
+        .get_reader_builder()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(sample.as_bytes())
+        .records()
+        .filter_map(Result::ok)
+        .collect()
 }
 
-/// Read the first line of a file
-pub fn read_first_line(file_path: &str) -> io::Result<String> {
-    let file: File = File::open(file_path).expect("Could not open file");
-    let reader: BufReader<File> = BufReader::new(file);
-    if let Some(line) = reader.lines().next() {
-        return line;
+/// The quote is the character opening the most fields, right at the start of a line or after a possible separator
+fn find_quote(sample: &str) -> char {
+    const POSSIBLE_QUOTES: [char; 2] = ['"', '\''];
+
+    let opened_fields = |quote: char| -> usize {
+        let mut previous: char = '\n';
+        let mut count: usize = 0;
+        for c in sample.chars() {
+            if c == quote && (previous == '\n' || POSSIBLE_SEPARATORS.contains(&previous)) {
+                count += 1;
+            }
+            previous = c;
+        }
+        count
+    };
+
+    let double_quoted: usize = opened_fields(POSSIBLE_QUOTES[0]);
+    let single_quoted: usize = opened_fields(POSSIBLE_QUOTES[1]);
+    if single_quoted > double_quoted {
+        return POSSIBLE_QUOTES[1];
     }
-    Err(io::Error::new(io::ErrorKind::NotFound, "File is empty"))
+    POSSIBLE_QUOTES[0]
+}
+
+/// Whether the sample stops inside a quoted field, escaped quotes don't close it and doubled ones close and reopen it
+fn ends_inside_quotes(sample: &str, quote: char, escape: Option<char>) -> bool {
+    let mut inside: bool = false;
+    let mut chars = sample.chars();
+    while let Some(c) = chars.next() {
+        if inside && Some(c) == escape {
+            chars.next();
+        } else if c == quote {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Quotes escaped with a backslash more often than doubled use the backslash escape style
+fn find_escape(sample: &str, quote: char) -> Option<char> {
+    let backslashed: usize = sample.matches(&format!("\\{quote}")).count();
+    let doubled: usize = sample.matches(&format!("{quote}{quote}")).count();
+    if backslashed > doubled {
+        return Some('\\');
+    }
+    None
+}
+
+/// Pick the separator splitting the most records into the same number of fields, the list order breaks ties
+fn find_separator(sample: &str, quote: char, escape: Option<char>) -> Option<char> {
+    let mut best: Option<(char, f64)> = None;
+
+    for separator in POSSIBLE_SEPARATORS {
+        if !sample.contains(separator) {
+            continue;
+        }
+
+        let dialect: CsvDialect = CsvDialect {
+            separator,
+            quote,
+            escape,
+            has_headers: false,
+        };
+        let records: Vec<StringRecord> = parse_sample(sample, &dialect);
+
+        // Most frequent number of fields, a separator must split the records in at least two fields
+        let mut widths: HashMap<usize, usize> = HashMap::new();
+        for record in records.iter() {
+            *widths.entry(record.len()).or_default() += 1;
+        }
+        let Some((width, count)) = widths
+            .into_iter()
+            .max_by_key(|(width, count)| (*count, *width))
+        else {
+            continue;
+        };
+        if width < 2 {
+            continue;
+        }
+
+        let consistency: f64 = count as f64 / records.len() as f64;
+        if best.is_none_or(|(_, best_consistency)| consistency > best_consistency) {
+            best = Some((separator, consistency));
+        }
+    }
+
+    best.map(|(separator, _)| separator)
+}
+
+/// A first row made of text above columns of numbers is a header, a first row of numbers is data.
+/// Without any numeric column to compare with, the first row is considered a header
+fn has_header_row(records: &[StringRecord]) -> bool {
+    let Some((first_row, rows)) = records.split_first() else {
+        return true;
+    };
+
+    let is_number = |value: &str| value.trim().parse::<f64>().is_ok();
+    let mut votes: i32 = 0;
+
+    for (i, header) in first_row.iter().enumerate() {
+        let mut values = rows
+            .iter()
+            .filter_map(|row| row.get(i))
+            .filter(|value| !value.trim().is_empty())
+            .peekable();
+        if values.peek().is_none() {
+            continue;
+        }
+
+        if values.all(is_number) {
+            votes += if is_number(header) { -1 } else { 1 };
+        }
+    }
+
+    votes >= 0
+}
+
+/// Column names of a csv file taken from its header row, parsed by the reader so that quoted separators and line breaks stay in a single column.
+/// Given column names replace the header row, without any of them a headerless file has its columns named after their position
+pub fn read_column_names(
+    reader: &mut Reader<impl Read>,
+    has_headers: bool,
+    column_names: &[String],
+    db_driver: &DatabaseEngine,
+) -> Result<Vec<String>, String> {
+    let names: Vec<String> = read_header_names(reader, has_headers, column_names)?;
+    Ok(StringFormatter::get_formated_column_names(
+        &names, db_driver,
+    ))
+}
+
+/// Column names of a csv file as written in its header row or given, before they are formatted.
+/// A headerless file without given names has empty ones
+pub fn read_header_names(
+    reader: &mut Reader<impl Read>,
+    has_headers: bool,
+    column_names: &[String],
+) -> Result<Vec<String>, String> {
+    let headers: &StringRecord = reader
+        .headers()
+        .map_err(|err| format!("Failed to read the header row: {err}"))?;
+
+    if !column_names.is_empty() && column_names.len() != headers.len() {
+        return Err(format!(
+            "{} column names were given but the file has {} columns",
+            column_names.len(),
+            headers.len()
+        ));
+    }
+
+    Ok(headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| match column_names.get(index) {
+            Some(name) => name.clone(),
+            None if has_headers => header.into(),
+            None => String::new(),
+        })
+        .collect())
+}
+
+/// Move the reader back to the first record of the file, so that the records can be read a second time.
+/// The reader yields the header row again after a seek, so it is skipped here.
+pub fn rewind_reader(reader: &mut Reader<impl Read + Seek>) -> Result<(), String> {
+    reader
+        .seek_raw(SeekFrom::Start(0), Position::new())
+        .map_err(|err| format!("Failed to rewind the file: {err}"))?;
+
+    if reader.has_headers() {
+        reader
+            .read_byte_record(&mut ByteRecord::new())
+            .map_err(|err| format!("Failed to skip the header row: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Get the size in bytes of a file, used to compute the progress of an import
+pub fn get_file_size(file_path: &str) -> io::Result<u64> {
+    let metadata: Metadata = std::fs::metadata(file_path)?;
+    if !metadata.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Path is not a file",
+        ));
+    }
+    Ok(metadata.len())
+}
+
+/// Fingerprint of a file, the SHA-256 digest of its whole content, used to detect that a file changed
+pub fn get_file_fingerprint(file_path: &str) -> io::Result<String> {
+    let mut hasher: Sha256 = Sha256::new();
+    io::copy(&mut BufReader::new(File::open(file_path)?), &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
 }
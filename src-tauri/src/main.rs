@@ -9,18 +9,25 @@ mod tests;
 use crate::fileflow::action::database_command::get_table_list;
 use fileflow::action::actions::*;
 use fileflow::action::database_command::*;
+use fileflow::stuct::job_registry::JobRegistry;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 fn main() {
     let database_state: Arc<DatabaseState> = Arc::new(DatabaseState(Mutex::new(None)));
+    let job_registry: Arc<JobRegistry> = Arc::new(JobRegistry::default());
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .manage(database_state)
+        .manage(job_registry)
         .invoke_handler(tauri::generate_handler![
             connect_to_database,
             insert_csv_data,
+            resume_import,
+            can_resume_import,
+            preview_import,
+            detect_schema_drift,
             disconnect_from_database,
             save_database_config,
             load_database_config_by_name,
@@ -29,7 +36,11 @@ fn main() {
             get_all_database_configs_name,
             delete_database_config,
             get_table_list,
-            download_table
+            get_schema_list,
+            download_table,
+            get_history,
+            clear_history,
+            cancel_job
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
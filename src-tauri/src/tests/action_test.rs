@@ -1,17 +1,49 @@
+use crate::fileflow::action::actions::{can_resume_import, preview_import};
+use crate::fileflow::action::database_command::{
+    append_insert, bulk_insert, fast_insert, get_column_types, match_table_columns,
+    optimized_insert, parallel_insert, upsert_insert,
+};
 use crate::fileflow::database::connection::Connection;
+use crate::fileflow::database::database_actions::{
+    begin_import, commit_import, get_table_columns, rollback_import,
+};
+use crate::fileflow::database::sql_builder::{
+    build_add_column_sql, build_create_with_fixed_size_sql, build_prepared_statement_sql,
+};
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::enumeration::optimized_strategy::OptimizedStrategy;
+use crate::fileflow::enumeration::reject_format::RejectFormat;
+use crate::fileflow::stuct::batch_outcome::BatchFailure;
+use crate::fileflow::stuct::cancel_token::CancelToken;
+use crate::fileflow::stuct::column_profile::ColumnProfile;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
 use crate::fileflow::stuct::db_config::DbConfig;
+use crate::fileflow::stuct::import_checkpoint::ImportCheckpoint;
+use crate::fileflow::stuct::import_context::ImportContext;
+use crate::fileflow::stuct::import_preview::ImportPreview;
+use crate::fileflow::stuct::insert_config::InsertConfig;
+use crate::fileflow::stuct::progress::Progress;
 use crate::fileflow::stuct::save_config::SaveConfig;
+use crate::fileflow::stuct::schema_drift::{IncompatibleColumn, SchemaDrift};
+use crate::fileflow::stuct::string_formater::StringFormatter;
+use crate::fileflow::stuct::table_column::TableColumn;
+use crate::fileflow::utils::csv_utils::{
+    get_file_fingerprint, read_column_names, read_header_names, sniff_dialect,
+};
 use crate::fileflow::utils::fileflowlib::{get_all_saved_configs, save_config};
 use crate::tests::utils_tests::{
-    create_test_db, delete_config_file, generate_csv_file, get_test_save_config,
-    get_test_sqlite_config, remove_csv_file, remove_test_db,
+    create_test_db, delete_config_file, generate_csv_file, generate_csv_file_with_content,
+    get_test_insert_config, get_test_save_config, get_test_sqlite_config, remove_csv_file,
+    remove_test_db,
 };
-use csv::{Reader, ReaderBuilder};
+use csv::{Reader, ReaderBuilder, StringRecord};
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Error, Pool, Row, Sqlite};
+use std::collections::HashMap;
 use std::fs::File;
-use crate::fileflow::action::database_command::fast_insert;
 
 #[tokio::test]
 async fn test_fast_insert() {
@@ -25,6 +57,7 @@ async fn test_fast_insert() {
 
     let csv_file_path: String =
         generate_csv_file("test_fast_insert").expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
 
     let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
     let final_table_name: &str = "test_table";
@@ -37,8 +70,10 @@ async fn test_fast_insert() {
         &conn,
         &mut reader,
         &snake_case_headers,
+        None,
         final_table_name,
         &DatabaseEngine::SQLite,
+        &mut context,
     )
     .await;
 
@@ -113,3 +148,1562 @@ async fn test_configs_serealization_deserialization() {
 
     delete_config_file(CONFIG_NAME).expect("Failed to delete config file");
 }
+
+#[tokio::test]
+async fn test_append_insert() {
+    let sqlite_file_path: String = create_test_db("append_insert");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    const SQL_ARRAY: [&str; 4] = [
+        "DROP TABLE IF EXISTS test_table",
+        "DROP TABLE IF EXISTS typed_table",
+        "CREATE TABLE test_table (id INTEGER PRIMARY KEY, header1 TEXT NOT NULL, header2 TEXT)",
+        "INSERT INTO test_table (header1, header2) VALUES ('existing1', 'existing2')",
+    ];
+
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    let csv_file_path: String =
+        generate_csv_file("test_append_insert").expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let inserted_count: u32 = append_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await
+    .expect("Failed to append data");
+
+    assert_eq!(inserted_count, 2, "Unexpected number of rows inserted");
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    let row: SqliteRow = sqlx::query("SELECT COUNT(*) AS total FROM test_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    assert_eq!(total, 3, "Existing rows must be kept");
+
+    let row: SqliteRow = sqlx::query("SELECT header1 FROM test_table WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch a row");
+    let value: String = row.get("header1");
+    assert_eq!(value, "existing1");
+
+    // Empty values of the non-text columns are stored as NULL and the others are converted to the column type
+    conn.query("CREATE TABLE typed_table (id INTEGER, amount DECIMAL(10, 2), created DATE)")
+        .await
+        .expect("Failed to create the typed table");
+    let typed_csv_file_path: String = generate_csv_file_with_content(
+        "test_append_insert_typed",
+        "id,amount,created\n1,,2024-01-01\n,2.5,\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut typed_reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&typed_csv_file_path).expect("Failed to open CSV file"));
+    append_insert(
+        &conn,
+        &mut typed_reader,
+        &["id".into(), "amount".into(), "created".into()],
+        None,
+        "typed_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await
+    .expect("Failed to append typed data");
+
+    let rows: Vec<SqliteRow> = sqlx::query(
+        "SELECT typeof(id) AS id_type, typeof(amount) AS amount_type, typeof(created) AS created_type FROM typed_table ORDER BY rowid",
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to fetch the typed rows");
+    let types: Vec<(String, String, String)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("id_type"),
+                row.get("amount_type"),
+                row.get("created_type"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            ("integer".into(), "null".into(), "text".into()),
+            ("null".into(), "real".into(), "null".into()),
+        ]
+    );
+    remove_csv_file("test_append_insert_typed").expect("Failed to remove CSV file");
+
+    // Appending into a table that does not exist must fail
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let result: Result<u32, String> = append_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "missing_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await;
+    assert!(result.is_err());
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("append_insert").expect("Failed to remove test table");
+    remove_csv_file("test_append_insert").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_match_table_columns() {
+    let mut table_columns: Vec<TableColumn> = vec![
+        TableColumn {
+            name: "id".into(),
+            data_type: "integer".into(),
+            max_length: None,
+            is_nullable: false,
+            has_default: true,
+        },
+        TableColumn {
+            name: "First Name".into(),
+            data_type: "text".into(),
+            max_length: None,
+            is_nullable: false,
+            has_default: false,
+        },
+        TableColumn {
+            name: "city".into(),
+            data_type: "text".into(),
+            max_length: None,
+            is_nullable: true,
+            has_default: false,
+        },
+    ];
+
+    assert_eq!(
+        match_table_columns(
+            &["first_name".into()],
+            &table_columns,
+            "t",
+            &DatabaseEngine::SQLite
+        )
+        .unwrap(),
+        vec!["First Name"]
+    );
+    assert_eq!(
+        match_table_columns(
+            &["city".into(), "first_name".into()],
+            &table_columns,
+            "t",
+            &DatabaseEngine::SQLite
+        )
+        .unwrap(),
+        vec!["city", "First Name"]
+    );
+    // A reserved word is matched with its rewritten csv column name
+    table_columns[2].name = "Order".into();
+    assert_eq!(
+        match_table_columns(
+            &["order_".into(), "first_name".into()],
+            &table_columns,
+            "t",
+            &DatabaseEngine::SQLite
+        )
+        .unwrap(),
+        vec!["Order", "First Name"]
+    );
+    assert_eq!(
+        match_table_columns(
+            &["order_".into()],
+            &table_columns,
+            "t",
+            &DatabaseEngine::SQLite
+        )
+        .unwrap_err(),
+        "Required column 'First Name' of table 't' is missing from the csv file"
+    );
+    assert_eq!(
+        match_table_columns(
+            &["first_name".into(), "country".into()],
+            &table_columns,
+            "t",
+            &DatabaseEngine::SQLite
+        )
+        .unwrap_err(),
+        "Column 'country' does not exist in table 't'"
+    );
+}
+
+#[tokio::test]
+async fn test_upsert_insert() {
+    let sqlite_file_path: String = create_test_db("upsert_insert");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    conn.query("DROP TABLE IF EXISTS test_table")
+        .await
+        .expect("Failed to drop the table");
+
+    let csv_file_path: String =
+        generate_csv_file("test_upsert_insert").expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+    let key_columns: Vec<String> = vec!["header1".into()];
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    // The first run creates the table, the second one must update the same rows
+    for _ in 0..2 {
+        let mut reader: Reader<File> = ReaderBuilder::new()
+            .has_headers(true)
+            .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+        let inserted_count: u32 = upsert_insert(
+            &conn,
+            &mut reader,
+            &snake_case_headers,
+            None,
+            "test_table",
+            &DatabaseEngine::SQLite,
+            &key_columns,
+            &ConflictPolicy::Update,
+            &mut context,
+        )
+        .await
+        .expect("Failed to upsert data");
+        assert_eq!(inserted_count, 2, "Unexpected number of rows upserted");
+
+        conn.query("UPDATE test_table SET header2 = 'changed' WHERE header1 = 'value1'")
+            .await
+            .expect("Failed to update a row");
+    }
+
+    let row: SqliteRow = sqlx::query("SELECT COUNT(*) AS total FROM test_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    assert_eq!(total, 2, "Rows with the same key must not be duplicated");
+
+    // With the ignore policy the existing row is kept as is, but still counted as read
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    upsert_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &key_columns,
+        &ConflictPolicy::Ignore,
+        &mut context,
+    )
+    .await
+    .expect("Failed to upsert data");
+
+    let row: SqliteRow = sqlx::query("SELECT header2 FROM test_table WHERE header1 = 'value1'")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch a row");
+    let value: String = row.get("header2");
+    assert_eq!(value, "changed");
+    assert_eq!(context.get_rows_read(), 2);
+
+    // A key column that is not in the csv file is rejected
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let result: Result<u32, String> = upsert_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &["unknown".into()],
+        &ConflictPolicy::Update,
+        &mut context,
+    )
+    .await;
+    assert!(result.is_err());
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("upsert_insert").expect("Failed to remove test table");
+    remove_csv_file("test_upsert_insert").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_upsert_insert_keys() {
+    let sqlite_file_path: String = create_test_db("upsert_insert_keys");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    const SQL_ARRAY: [&str; 3] = [
+        "DROP TABLE IF EXISTS test_table",
+        "DROP TABLE IF EXISTS keyless_table",
+        "CREATE TABLE keyless_table (id TEXT, name TEXT)",
+    ];
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the tables");
+    }
+
+    let csv_file_path: String =
+        generate_csv_file_with_content("test_upsert_insert_keys", "id,name\n1,a\n2,b\n1,c\n")
+            .expect("Failed to generate csv file");
+    let fingerprint: String =
+        get_file_fingerprint(&csv_file_path).expect("Failed to fingerprint file");
+    let snake_case_headers: Vec<String> = vec!["id".into(), "name".into()];
+    let key_columns: Vec<String> = vec!["id".into()];
+
+    // An existing table without a unique index on the key columns can't be upserted
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let result: Result<u32, String> = upsert_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "keyless_table",
+        &DatabaseEngine::SQLite,
+        &key_columns,
+        &ConflictPolicy::Update,
+        &mut context,
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err(),
+        "Table 'keyless_table' has no unique index or primary key on the key columns id"
+    );
+
+    // The duplicate key merged into the batch is the furthest record read, the checkpoint is saved past it
+    let mut context: ImportContext =
+        ImportContext::new(&csv_file_path, ',', RejectFormat::Csv).with_checkpoint(
+            ImportCheckpoint::new(&csv_file_path, &fingerprint, "test_table"),
+        );
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let inserted_count: u32 = upsert_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &key_columns,
+        &ConflictPolicy::Update,
+        &mut context,
+    )
+    .await
+    .expect("Failed to upsert data");
+    assert_eq!(inserted_count, 2);
+
+    let checkpoint: ImportCheckpoint =
+        ImportCheckpoint::load(&csv_file_path).expect("Failed to load the checkpoint");
+    assert_eq!(checkpoint.record, 3);
+    assert_eq!(checkpoint.rows_committed, 2);
+    context.remove_checkpoint();
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+    let row: SqliteRow = sqlx::query("SELECT name FROM test_table WHERE id = '1'")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch a row");
+    let value: String = row.get("name");
+    assert_eq!(value, "c");
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("upsert_insert_keys").expect("Failed to remove test table");
+    remove_csv_file("test_upsert_insert_keys").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_duplicate_and_reserved_headers() {
+    let sqlite_file_path: String = create_test_db("duplicate_headers");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+    let db_driver: &DatabaseEngine = &DatabaseEngine::SQLite;
+
+    const SQL_ARRAY: [&str; 3] = [
+        "DROP TABLE IF EXISTS test_table",
+        "DROP TABLE IF EXISTS existing_table",
+        "CREATE TABLE existing_table (\"order\" TEXT, \"Order_\" TEXT)",
+    ];
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_duplicate_headers",
+        "Name,name,Order,name_2\nalice,a1,1,x\nbob,b1,2,y\n",
+    )
+    .expect("Failed to generate csv file");
+    let open_reader = || {
+        ReaderBuilder::new()
+            .has_headers(true)
+            .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"))
+    };
+
+    let header_names: Vec<String> =
+        read_header_names(&mut open_reader(), true, &[]).expect("Failed to read headers");
+    let column_names: Vec<String> =
+        StringFormatter::get_formated_column_names(&header_names, db_driver);
+    assert_eq!(column_names, vec!["name", "name_2", "order_", "name_2_2"]);
+
+    // A key is looked up among the headers first, so the header `name_2` is the deduplicated `name_2_2` column
+    let key_names = |keys: &[&str]| {
+        StringFormatter::get_formated_key_names(
+            &keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<String>>(),
+            &header_names,
+            &column_names,
+            db_driver,
+        )
+    };
+    assert_eq!(key_names(&["name_2"]), Ok(vec!["name_2_2".into()]));
+    assert_eq!(key_names(&["name"]), Ok(vec!["name_2".into()]));
+    assert_eq!(
+        key_names(&["order_", "NAME"]),
+        Ok(vec!["order_".into(), "name".into()])
+    );
+    assert!(key_names(&["unknown"]).is_err());
+
+    // Upserting twice on the reserved `Order` header keeps a single row per key
+    let key_columns: Vec<String> = key_names(&["Order"]).expect("Failed to find the key columns");
+    for _ in 0..2 {
+        let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+        let upserted_count: u32 = upsert_insert(
+            &conn,
+            &mut open_reader(),
+            &column_names,
+            None,
+            "test_table",
+            db_driver,
+            &key_columns,
+            &ConflictPolicy::Update,
+            &mut context,
+        )
+        .await
+        .expect("Failed to upsert data");
+        assert_eq!(upserted_count, 2);
+    }
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+    let rows: Vec<SqliteRow> =
+        sqlx::query("SELECT name, name_2, name_2_2 FROM test_table ORDER BY order_")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to fetch rows");
+    let values: Vec<(String, String, String)> = rows
+        .iter()
+        .map(|row| (row.get("name"), row.get("name_2"), row.get("name_2_2")))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ("alice".into(), "a1".into(), "x".into()),
+            ("bob".into(), "b1".into(), "y".into())
+        ]
+    );
+
+    // Table columns colliding once formatted are deduplicated like the headers they match
+    let append_file_path: String =
+        generate_csv_file_with_content("test_duplicate_headers_append", "order,Order_\n1,a\n")
+            .expect("Failed to generate csv file");
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&append_file_path).expect("Failed to open CSV file"));
+    let append_columns: Vec<String> =
+        read_column_names(&mut reader, true, &[], db_driver).expect("Failed to read headers");
+    assert_eq!(append_columns, vec!["order_", "order__2"]);
+    let mut context: ImportContext = ImportContext::new(&append_file_path, ',', RejectFormat::Csv);
+    let appended_count: u32 = append_insert(
+        &conn,
+        &mut reader,
+        &append_columns,
+        None,
+        "existing_table",
+        db_driver,
+        &mut context,
+    )
+    .await
+    .expect("Failed to append data");
+    assert_eq!(appended_count, 1);
+
+    let row: SqliteRow = sqlx::query("SELECT \"order\", \"Order_\" FROM existing_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch a row");
+    let order: String = row.get("order");
+    let order_2: String = row.get("Order_");
+    assert_eq!((order.as_str(), order_2.as_str()), ("1", "a"));
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("duplicate_headers").expect("Failed to remove test table");
+    remove_csv_file("test_duplicate_headers").expect("Failed to remove CSV file");
+    remove_csv_file("test_duplicate_headers_append").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_bulk_insert_requires_mysql() {
+    let sqlite_file_path: String = create_test_db("bulk_insert");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path);
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String =
+        generate_csv_file("test_bulk_insert").expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let result: Result<u32, String> = bulk_insert(
+        &conn,
+        &mut reader,
+        &["header1".into(), "header2".into()],
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err(),
+        "The bulk load is only available for MySQL and MariaDB"
+    );
+
+    drop(conn);
+    remove_test_db("bulk_insert").expect("Failed to remove test table");
+    remove_csv_file("test_bulk_insert").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_fast_insert_keeps_values_unchanged() {
+    let sqlite_file_path: String = create_test_db("fast_insert_unchanged");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_fast_insert_unchanged",
+        "header1,header2\n\"  say \"\"hi\"\"  \",\"line1\nline2\"\nO'Brien,back\\slash\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let inserted_count: u32 = fast_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    assert_eq!(inserted_count, 2, "Unexpected number of rows inserted");
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    let rows: Vec<SqliteRow> =
+        sqlx::query("SELECT header1, header2 FROM test_table ORDER BY rowid")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to fetch rows");
+
+    let first: (String, String) = (rows[0].get("header1"), rows[0].get("header2"));
+    let second: (String, String) = (rows[1].get("header1"), rows[1].get("header2"));
+    assert_eq!(first, ("  say \"hi\"  ".into(), "line1\nline2".into()));
+    assert_eq!(second, ("O'Brien".into(), "back\\slash".into()));
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("fast_insert_unchanged").expect("Failed to remove test table");
+    remove_csv_file("test_fast_insert_unchanged").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_optimized_insert_infers_types() {
+    let sqlite_file_path: String = create_test_db("optimized_insert_types");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_optimized_insert_types",
+        "id,price,active,day,name\n1,10.50,true,2024-01-01,alice\n2,,false,2024-01-02,bob\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec![
+        "id".into(),
+        "price".into(),
+        "active".into(),
+        "day".into(),
+        "name".into(),
+    ];
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let inserted_count: u32 = optimized_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &OptimizedStrategy::TemporaryTable,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    assert_eq!(inserted_count, 2, "Unexpected number of rows inserted");
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    let rows: Vec<SqliteRow> = sqlx::query("SELECT type FROM pragma_table_info('test_table')")
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch the table definition");
+    let types: Vec<String> = rows.iter().map(|row| row.get("type")).collect();
+    assert_eq!(
+        types,
+        vec!["INTEGER", "DECIMAL(4,2)", "BOOLEAN", "DATE", "VARCHAR(6)"]
+    );
+
+    let row: SqliteRow = sqlx::query("SELECT price, active FROM test_table WHERE id = 2")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch a row");
+    let price: Option<f64> = row.get("price");
+    let active: bool = row.get("active");
+    assert_eq!(price, None, "Empty values must become NULL");
+    assert!(!active);
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("optimized_insert_types").expect("Failed to remove test table");
+    remove_csv_file("test_optimized_insert_types").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_wide_columns_fit_mysql_row_size() {
+    // MySQL rows hold at most 65,535 bytes, a `utf8mb4` VARCHAR taking up to 4 bytes per character
+    const MAX_ROW_BYTES: usize = 65_535;
+    let csv: InsertConfig =
+        get_test_insert_config("wide.csv", InsertionType::Optimized, DatabaseEngine::MySQL);
+    let column_names: Vec<String> = (1..=60).map(|i| format!("column_{i}")).collect();
+
+    for width in [250, 700] {
+        let mut profile: ColumnProfile = ColumnProfile::default();
+        profile.observe(&"é".repeat(width));
+        let columns_profile: Vec<ColumnProfile> = vec![profile; column_names.len()];
+        let column_types: HashMap<&str, ColumnType> =
+            get_column_types(&column_names, &columns_profile, &csv.get_varchar_sizing());
+
+        let row_bytes: usize = column_types
+            .values()
+            .map(|column_type| match column_type {
+                ColumnType::Varchar(length) => length * 4,
+                _ => 0,
+            })
+            .sum();
+        assert!(row_bytes <= MAX_ROW_BYTES, "{width}: {row_bytes} bytes");
+
+        let ddl: String = build_create_with_fixed_size_sql(
+            &DatabaseEngine::MySQL,
+            None,
+            "wide_table",
+            &column_types,
+            &column_names,
+        );
+        let expected_type: &str = if width < 255 { "VARCHAR(251)" } else { "TEXT" };
+        assert_eq!(ddl.matches(expected_type).count(), column_names.len());
+    }
+}
+
+#[tokio::test]
+async fn test_optimized_insert_two_pass_headerless() {
+    let sqlite_file_path: String = create_test_db("optimized_insert_two_pass");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_optimized_insert_two_pass",
+        "1,yes,alice\n2,no,bob\n3,,carol\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let columns: Vec<String> = read_column_names(&mut reader, false, &[], &DatabaseEngine::SQLite)
+        .expect("Failed to read the column names");
+
+    // The first record, read as the header row of a headerless file, must be inserted by the second pass too
+    let inserted_count: u32 = optimized_insert(
+        &conn,
+        &mut reader,
+        &columns,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &OptimizedStrategy::TwoPass,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    assert_eq!(inserted_count, 3, "Unexpected number of rows inserted");
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    let rows: Vec<SqliteRow> = sqlx::query("SELECT type FROM pragma_table_info('test_table')")
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch the table definition");
+    let types: Vec<String> = rows.iter().map(|row| row.get("type")).collect();
+    assert_eq!(types, vec!["INTEGER", "BOOLEAN", "VARCHAR(6)"]);
+
+    let rows: Vec<SqliteRow> = sqlx::query("SELECT * FROM test_table ORDER BY column_1")
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch the rows");
+    let values: Vec<(i64, Option<bool>, String)> = rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2)))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            (1, Some(true), "alice".into()),
+            (2, Some(false), "bob".into()),
+            (3, None, "carol".into()),
+        ]
+    );
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("optimized_insert_two_pass").expect("Failed to remove test table");
+    remove_csv_file("test_optimized_insert_two_pass").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_optimized_strategy_not_supported() {
+    let sqlite_file_path: String = create_test_db("optimized_insert_alter");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String =
+        generate_csv_file_with_content("test_optimized_insert_alter", "id\n1\n")
+            .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let result: Result<u32, String> = optimized_insert(
+        &conn,
+        &mut reader,
+        &["id".into()],
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &OptimizedStrategy::AlterInPlace,
+        &mut context,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Only Postgres can alter the columns in place"
+    );
+
+    drop(conn);
+    remove_test_db("optimized_insert_alter").expect("Failed to remove test table");
+    remove_csv_file("test_optimized_insert_alter").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_preview_import() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_preview_import",
+        "Id;Active;Name\n1;yes;alice\n2;no;bob\n3;;carol\n",
+    )
+    .expect("Failed to generate csv file");
+
+    // The optimized mode profiles the whole file, even the rows left out of the preview
+    let mut csv: InsertConfig = get_test_insert_config(
+        &csv_file_path,
+        InsertionType::Optimized,
+        DatabaseEngine::Postgres,
+    );
+    csv.schema = Some("staging".into());
+    csv.optimized_strategy = Some(OptimizedStrategy::TwoPass);
+    let preview: ImportPreview = preview_import(csv, Some(2))
+        .await
+        .expect("Failed to preview the import");
+
+    assert_eq!(preview.table_name, "staging.test_table");
+    assert_eq!(preview.dialect.separator, ';');
+    assert_eq!(preview.column_names, vec!["id", "active", "name"]);
+    assert_eq!(
+        preview.rows,
+        vec![vec!["1", "1", "alice"], vec!["2", "0", "bob"]]
+    );
+    assert_eq!(
+        preview.ddl,
+        vec!["CREATE TABLE \"staging\".\"test_table\" (\"id\" INTEGER, \"active\" BOOLEAN, \"name\" VARCHAR(6));"]
+    );
+    assert_eq!(
+        preview.insert_sql,
+        "INSERT INTO \"staging\".\"test_table\" (\"id\", \"active\", \"name\") "
+    );
+
+    // A fast import into Postgres loads the file with COPY
+    let csv: InsertConfig = get_test_insert_config(
+        &csv_file_path,
+        InsertionType::Fast,
+        DatabaseEngine::Postgres,
+    );
+    let preview: ImportPreview = preview_import(csv, Some(2))
+        .await
+        .expect("Failed to preview the import");
+    assert_eq!(
+        preview.insert_sql,
+        "COPY \"test_table\" (\"id\", \"active\", \"name\") FROM STDIN (FORMAT csv, HEADER true, DELIMITER ';', QUOTE '\"', FORCE_NOT_NULL (\"id\", \"active\", \"name\"))"
+    );
+
+    // An upsert needs its key columns in the file
+    let mut csv: InsertConfig = get_test_insert_config(
+        &csv_file_path,
+        InsertionType::Upsert,
+        DatabaseEngine::SQLite,
+    );
+    csv.key_columns = vec!["code".into()];
+    assert!(preview_import(csv, None).await.is_err());
+
+    remove_csv_file("test_preview_import").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_detect_schema_drift() {
+    let sqlite_file_path: String = create_test_db("schema_drift");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+    conn.query("DROP TABLE IF EXISTS orders")
+        .await
+        .expect("Failed to prepare the table");
+    conn.query("CREATE TABLE orders (id INTEGER, customer_name TEXT, amount INTEGER, legacy TEXT)")
+        .await
+        .expect("Failed to prepare the table");
+
+    let csv_columns: Vec<String> = vec![
+        "id".into(),
+        "customer_full_name".into(),
+        "amount".into(),
+        "note".into(),
+        "empty".into(),
+    ];
+    // The all-empty column has no inferred type
+    let column_types: HashMap<&str, ColumnType> = HashMap::from([
+        ("id", ColumnType::Integer),
+        ("customer_full_name", ColumnType::Varchar(5)),
+        (
+            "amount",
+            ColumnType::Decimal {
+                precision: 3,
+                scale: 1,
+            },
+        ),
+        ("note", ColumnType::Varchar(3)),
+    ]);
+
+    let table_columns: Vec<TableColumn> =
+        get_table_columns(&conn, &DatabaseEngine::SQLite, None, "orders")
+            .await
+            .expect("Failed to get the table columns");
+    let drift: SchemaDrift = SchemaDrift::compare(
+        "orders",
+        &csv_columns,
+        &column_types,
+        &table_columns,
+        &DatabaseEngine::SQLite,
+    );
+
+    assert!(drift.table_exists);
+    assert_eq!(drift.added, vec!["note", "empty"]);
+    assert_eq!(drift.missing, vec!["legacy"]);
+    assert_eq!(drift.renamed.len(), 1);
+    assert_eq!(drift.renamed[0].table_column, "customer_name");
+    assert_eq!(drift.renamed[0].csv_column, "customer_full_name");
+    assert_eq!(
+        drift.incompatible,
+        vec![IncompatibleColumn {
+            table_column: "amount".into(),
+            csv_column: "amount".into(),
+            table_type: "integer".into(),
+            csv_type: ColumnType::Decimal {
+                precision: 3,
+                scale: 1
+            },
+        }]
+    );
+    assert_eq!(drift.get_warnings().len(), 3);
+
+    // Once added, the new columns of the file are matched with the table
+    for (column, column_type) in [
+        ("note", ColumnType::Varchar(3)),
+        ("empty", ColumnType::Text),
+    ] {
+        conn.query(&build_add_column_sql(
+            &DatabaseEngine::SQLite,
+            None,
+            "orders",
+            column,
+            &column_type,
+        ))
+        .await
+        .expect("Failed to add the column");
+    }
+    let table_columns: Vec<TableColumn> =
+        get_table_columns(&conn, &DatabaseEngine::SQLite, None, "orders")
+            .await
+            .expect("Failed to get the table columns");
+    let drift: SchemaDrift = SchemaDrift::compare(
+        "orders",
+        &csv_columns,
+        &column_types,
+        &table_columns,
+        &DatabaseEngine::SQLite,
+    );
+    assert!(drift.added.is_empty());
+    assert_eq!(table_columns[4].data_type, "varchar(3)");
+
+    // A table that does not exist has no drift
+    let drift: SchemaDrift = SchemaDrift::compare(
+        "missing_table",
+        &csv_columns,
+        &column_types,
+        &[],
+        &DatabaseEngine::SQLite,
+    );
+    assert!(!drift.table_exists);
+    assert!(drift.added.is_empty());
+
+    drop(conn);
+    remove_test_db("schema_drift").expect("Failed to remove test table");
+}
+
+#[tokio::test]
+async fn test_transactional_import_rollback() {
+    let sqlite_file_path: String = create_test_db("transactional_import");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect_transactional(&config)
+        .await
+        .expect("Failed to connect to the database");
+    assert!(conn.is_transactional());
+
+    const SQL_ARRAY: [&str; 3] = [
+        "DROP TABLE IF EXISTS test_table",
+        "CREATE TABLE test_table (header1 TEXT CHECK (header1 <> 'invalid'), header2 TEXT)",
+        "INSERT INTO test_table (header1, header2) VALUES ('existing1', 'existing2')",
+    ];
+
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    // The first batch is valid, the second one is rejected by the check constraint
+    let mut content: String = String::from("header1,header2\n");
+    for i in 0..6_000 {
+        content.push_str(&format!("value{i},value{i}\n"));
+    }
+    content.push_str("invalid,value\n");
+    let csv_file_path: String =
+        generate_csv_file_with_content("test_transactional_import", &content)
+            .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+    let db_driver: &DatabaseEngine = &DatabaseEngine::SQLite;
+
+    let target_table_name: String =
+        begin_import(&conn, db_driver, None, "test_table", &InsertionType::Append)
+            .await
+            .expect("Failed to begin the import");
+    assert_eq!(target_table_name, "test_table");
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let result: Result<u32, String> = append_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        &target_table_name,
+        db_driver,
+        &mut context,
+    )
+    .await;
+    assert!(result.is_err(), "A failed batch must stop the import");
+
+    rollback_import(&conn, db_driver, None, &target_table_name)
+        .await
+        .expect("Failed to rollback the import");
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to create a connection pool");
+
+    let row: SqliteRow = sqlx::query("SELECT COUNT(*) AS total FROM test_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    assert_eq!(total, 1, "The previous rows must be left untouched");
+
+    // A successful import is kept once committed
+    let target_table_name: String =
+        begin_import(&conn, db_driver, None, "test_table", &InsertionType::Fast)
+            .await
+            .expect("Failed to begin the import");
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    fast_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        &target_table_name,
+        db_driver,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    commit_import(&conn, db_driver, None, "test_table", &target_table_name)
+        .await
+        .expect("Failed to commit the import");
+
+    let row: SqliteRow = sqlx::query("SELECT COUNT(*) AS total FROM test_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    assert_eq!(total, 6_001);
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("transactional_import").expect("Failed to remove test table");
+    remove_csv_file("test_transactional_import").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_failed_batch_rejects_faulty_rows() {
+    let sqlite_file_path: String = create_test_db("failed_batch_rejects");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    const SQL_ARRAY: [&str; 2] = [
+        "DROP TABLE IF EXISTS test_table",
+        "CREATE TABLE test_table (header1 TEXT CHECK (header1 <> 'invalid'), header2 TEXT)",
+    ];
+
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_failed_batch_rejects",
+        "header1,header2\nvalue1,value2\ninvalid,value3\nvalue4\nvalue5,value6\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let inserted_count: u32 = append_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await
+    .expect("Failed to append data");
+    context
+        .rejects
+        .flush()
+        .expect("Failed to flush reject file");
+
+    // The malformed record and the one refused by the check constraint are the only ones rejected
+    assert_eq!(inserted_count, 2, "Unexpected number of rows inserted");
+    assert_eq!(context.rejects.get_rejected_count(), 2);
+
+    // The refused statement is reported once, from the line of its first row
+    let failures: &[BatchFailure] = context.get_batch_failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].first_line, Some(2));
+    assert_eq!(failures[0].row_count, 3);
+    assert!(failures[0].error.contains("CHECK constraint failed"));
+
+    let mut rejects: Reader<File> = ReaderBuilder::new().from_reader(
+        File::open(context.rejects.get_reject_path()).expect("Failed to open reject file"),
+    );
+    let rejected: Vec<StringRecord> = rejects.records().map(|r| r.unwrap()).collect();
+    assert_eq!(&rejected[0][0], "4");
+    assert_eq!(&rejected[0][2], "value4");
+    assert_eq!(&rejected[1][0], "3");
+    assert_eq!(&rejected[1][2], "invalid,value3");
+    assert!(rejected[1][1].contains("CHECK constraint failed"));
+
+    std::fs::remove_file(context.rejects.get_reject_path()).expect("Failed to remove reject file");
+    drop(conn);
+
+    remove_test_db("failed_batch_rejects").expect("Failed to remove test table");
+    remove_csv_file("test_failed_batch_rejects").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_cancelled_import_stops_before_inserting() {
+    let sqlite_file_path: String = create_test_db("cancelled_import");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String =
+        generate_csv_file("test_cancelled_import").expect("Failed to generate csv file");
+    let cancel_token: CancelToken = CancelToken::default();
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv)
+        .with_cancel_token(cancel_token.clone());
+    cancel_token.cancel();
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let result: Result<u32, String> = fast_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &mut context,
+    )
+    .await;
+    assert_eq!(result, Err("Import cancelled".into()));
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to connect to the database");
+    let row: SqliteRow = sqlx::query("SELECT COUNT(*) AS total FROM test_table")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    assert_eq!(total, 0, "No row should be inserted once cancelled");
+
+    pool.close().await;
+    drop(conn);
+    remove_csv_file("test_cancelled_import").expect("Failed to remove csv file");
+    remove_test_db("cancelled_import").expect("Failed to remove test table");
+}
+
+#[tokio::test]
+async fn test_resume_import_from_checkpoint() {
+    let sqlite_file_path: String = create_test_db("resume_import");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let mut content: String = String::from("header1,header2\n");
+    for i in 0..6_001 {
+        content.push_str(&format!("value{i},value{i}\n"));
+    }
+    let csv_file_path: String = generate_csv_file_with_content("test_resume_import", &content)
+        .expect("Failed to generate csv file");
+    let fingerprint: String =
+        get_file_fingerprint(&csv_file_path).expect("Failed to fingerprint file");
+
+    let snake_case_headers: Vec<String> = vec!["header1".into(), "header2".into()];
+    let db_driver: &DatabaseEngine = &DatabaseEngine::SQLite;
+
+    // The import is interrupted once its first full batch is committed
+    let cancel_token: CancelToken = CancelToken::default();
+    let listener_token: CancelToken = cancel_token.clone();
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv)
+        .with_checkpoint(ImportCheckpoint::new(
+            &csv_file_path,
+            &fingerprint,
+            "test_table",
+        ))
+        .with_cancel_token(cancel_token)
+        .with_progress_listener(Box::new(move |_: &Progress| listener_token.cancel()));
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let result: Result<u32, String> = fast_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        db_driver,
+        &mut context,
+    )
+    .await;
+    assert!(result.is_err());
+
+    // The checkpoint is saved after the first full batch
+    let checkpoint: ImportCheckpoint =
+        ImportCheckpoint::load(&csv_file_path).expect("Failed to load the checkpoint");
+    assert_eq!(checkpoint.rows_committed, 5_000);
+    assert_eq!(checkpoint.table_name, "test_table");
+    assert_eq!(checkpoint.fingerprint, fingerprint);
+    assert_eq!(checkpoint.validate("test_table"), Ok(fingerprint.clone()));
+    assert!(checkpoint.validate("other_table").is_err());
+
+    // Only a resumable run into the checkpointed table offers to resume
+    let resumable = |mode: InsertionType, transactional: bool, table_name: &str| {
+        let mut csv: InsertConfig =
+            get_test_insert_config(&csv_file_path, mode, DatabaseEngine::SQLite);
+        csv.transactional = transactional;
+        csv.table_name = table_name.into();
+        can_resume_import(csv)
+    };
+    assert_eq!(
+        resumable(InsertionType::Fast, false, "test_table").await,
+        Ok(true)
+    );
+    assert_eq!(
+        resumable(InsertionType::Fast, true, "test_table").await,
+        Ok(false)
+    );
+    assert_eq!(
+        resumable(InsertionType::Optimized, false, "test_table").await,
+        Ok(false)
+    );
+    assert_eq!(
+        resumable(InsertionType::Append, false, "other_table").await,
+        Ok(false)
+    );
+
+    let mut context: ImportContext =
+        ImportContext::new(&csv_file_path, ',', RejectFormat::Csv).with_checkpoint(checkpoint);
+    assert!(context.is_resumed());
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    ImportCheckpoint::load(&csv_file_path)
+        .expect("Failed to load the checkpoint")
+        .seek_reader(&mut reader)
+        .expect("Failed to seek to the checkpoint");
+
+    let inserted_count: u32 = fast_insert(
+        &conn,
+        &mut reader,
+        &snake_case_headers,
+        None,
+        "test_table",
+        db_driver,
+        &mut context,
+    )
+    .await
+    .expect("Failed to resume the import");
+    assert_eq!(
+        inserted_count, 1_001,
+        "Only the remaining rows are inserted"
+    );
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to connect to the database");
+    let row: SqliteRow = sqlx::query(
+        "SELECT COUNT(*) AS total, COUNT(DISTINCT header1) AS distinct_total FROM test_table",
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    let distinct_total: i64 = row.get("distinct_total");
+    assert_eq!(total, 6_001);
+    assert_eq!(distinct_total, 6_001, "No row should be inserted twice");
+
+    // The last partial batch is checkpointed too
+    assert_eq!(
+        ImportCheckpoint::load(&csv_file_path)
+            .expect("Failed to load the checkpoint")
+            .rows_committed,
+        6_001
+    );
+
+    // A file changed past its first bytes, even keeping its size, can't be resumed
+    std::fs::write(
+        &csv_file_path,
+        content.replace("value6000,value6000", "value6000,value6001"),
+    )
+    .expect("Failed to write csv file");
+    assert!(ImportCheckpoint::load(&csv_file_path)
+        .expect("Failed to load the checkpoint")
+        .validate("test_table")
+        .is_err());
+
+    context.remove_checkpoint();
+    assert!(ImportCheckpoint::load(&csv_file_path).is_none());
+
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("resume_import").expect("Failed to remove test table");
+    remove_csv_file("test_resume_import").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_parallel_insert_counts_every_row() {
+    let sqlite_file_path: String = create_test_db("parallel_insert");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    const SQL_ARRAY: [&str; 2] = [
+        "DROP TABLE IF EXISTS test_table",
+        "CREATE TABLE test_table (header1 TEXT CHECK (header1 <> 'invalid'), header2 TEXT)",
+    ];
+
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    let mut content: String = String::from("header1,header2\n");
+    for i in 0..12_000 {
+        content.push_str(&format!("value{i},value{i}\n"));
+    }
+    content.push_str("invalid,value\n");
+    let csv_file_path: String = generate_csv_file_with_content("test_parallel_insert", &content)
+        .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+
+    let insert_query_base: String = build_prepared_statement_sql(
+        &DatabaseEngine::SQLite,
+        None,
+        "test_table",
+        &["header1".into(), "header2".into()],
+    );
+    let inserted_count: u32 = parallel_insert(
+        &conn,
+        &mut reader,
+        &insert_query_base,
+        "test_table",
+        3,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    context
+        .rejects
+        .flush()
+        .expect("Failed to flush reject file");
+
+    assert_eq!(inserted_count, 12_000, "Unexpected number of rows inserted");
+    assert_eq!(context.rejects.get_rejected_count(), 1);
+
+    let pool: Pool<Sqlite> = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&sqlite_file_path)
+        .await
+        .expect("Failed to connect to the database");
+    let row: SqliteRow = sqlx::query(
+        "SELECT COUNT(*) AS total, COUNT(DISTINCT header1) AS distinct_total FROM test_table",
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to count rows");
+    let total: i64 = row.get("total");
+    let distinct_total: i64 = row.get("distinct_total");
+    assert_eq!(total, 12_000);
+    assert_eq!(distinct_total, 12_000);
+
+    std::fs::remove_file(context.rejects.get_reject_path()).expect("Failed to remove reject file");
+    pool.close().await;
+    drop(conn);
+
+    remove_test_db("parallel_insert").expect("Failed to remove test table");
+    remove_csv_file("test_parallel_insert").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_optimized_insert_with_quoted_headers() {
+    let sqlite_file_path: String = create_test_db("optimized_insert_quoted_headers");
+    let config: DbConfig = get_test_sqlite_config(sqlite_file_path.clone());
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_optimized_insert_quoted_headers",
+        "\"City State\",code\n\"Austin, TX\",1\nBoston,2,extra\n\"Denver, CO\",3\n",
+    )
+    .expect("Failed to generate csv file");
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv);
+
+    let dialect: CsvDialect = sniff_dialect(&csv_file_path).expect("Failed to sniff dialect");
+    let mut reader: Reader<File> = dialect
+        .get_reader_builder()
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let columns: Vec<String> = read_column_names(
+        &mut reader,
+        dialect.has_headers,
+        &[],
+        &DatabaseEngine::SQLite,
+    )
+    .expect("Failed to read headers");
+    assert_eq!(columns, vec!["city_state", "code"]);
+
+    // The record wider than the header is rejected instead of breaking the import
+    let inserted_count: u32 = optimized_insert(
+        &conn,
+        &mut reader,
+        &columns,
+        None,
+        "test_table",
+        &DatabaseEngine::SQLite,
+        &OptimizedStrategy::TwoPass,
+        &mut context,
+    )
+    .await
+    .expect("Failed to insert data");
+    context
+        .rejects
+        .flush()
+        .expect("Failed to flush reject file");
+    assert_eq!(inserted_count, 2, "Unexpected number of rows inserted");
+    assert_eq!(context.rejects.get_rejected_count(), 1);
+
+    std::fs::remove_file(context.rejects.get_reject_path()).expect("Failed to remove reject file");
+    drop(conn);
+
+    remove_test_db("optimized_insert_quoted_headers").expect("Failed to remove test table");
+    remove_csv_file("test_optimized_insert_quoted_headers").expect("Failed to remove CSV file");
+}
@@ -0,0 +1,164 @@
+use crate::fileflow::enumeration::column_type::ColumnType;
+use crate::fileflow::enumeration::length_unit::LengthUnit;
+use crate::fileflow::stuct::column_profile::ColumnProfile;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+
+/// Build a profile from a list of values
+fn profile_of(values: &[&str]) -> ColumnProfile {
+    let mut profile: ColumnProfile = ColumnProfile::default();
+    for value in values {
+        profile.observe(value);
+    }
+    profile
+}
+
+/// Infer the type of a column with the default VARCHAR sizing
+fn column_type_of(values: &[&str]) -> ColumnType {
+    profile_of(values).column_type(&VarcharSizing::default())
+}
+
+#[tokio::test]
+async fn test_infer_numeric_types() {
+    assert_eq!(column_type_of(&["1", "-42", "0"]), ColumnType::Integer);
+    assert_eq!(
+        column_type_of(&["1", "9000000000"]),
+        ColumnType::BigInt
+    );
+    assert_eq!(
+        column_type_of(&["1.5", "-123.25"]),
+        ColumnType::Decimal {
+            precision: 5,
+            scale: 2
+        }
+    );
+    assert_eq!(
+        column_type_of(&["12345", "0.125"]),
+        ColumnType::Decimal {
+            precision: 8,
+            scale: 3
+        }
+    );
+    assert_eq!(
+        column_type_of(&["123456789012345678901234567890"]),
+        ColumnType::Decimal {
+            precision: 30,
+            scale: 0
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_infer_boolean_and_temporal_types() {
+    assert_eq!(
+        column_type_of(&["true", "FALSE", "yes"]),
+        ColumnType::Boolean
+    );
+    assert_eq!(
+        column_type_of(&["2024-02-29", "1999-12-31"]),
+        ColumnType::Date
+    );
+    assert_eq!(
+        column_type_of(&["2024-01-01", "2024-01-01T10:30:00.123"]),
+        ColumnType::Timestamp
+    );
+    assert_eq!(
+        column_type_of(&["2024-01-01 23:59:59"]),
+        ColumnType::Timestamp
+    );
+}
+
+#[tokio::test]
+async fn test_widen_to_text() {
+    // Leading zeros, invalid dates and mixed families are kept as text
+    assert_eq!(column_type_of(&["01234"]), ColumnType::Varchar(6));
+    assert_eq!(column_type_of(&["2023-02-29"]), ColumnType::Varchar(11));
+    assert_eq!(column_type_of(&["1", "true"]), ColumnType::Varchar(5));
+    assert_eq!(
+        column_type_of(&["2024-01-01", "12"]),
+        ColumnType::Varchar(11)
+    );
+    assert_eq!(column_type_of(&[" 12"]), ColumnType::Varchar(4));
+    assert_eq!(column_type_of(&["1.", ".5"]), ColumnType::Varchar(3));
+    assert_eq!(column_type_of(&[&"a".repeat(300)]), ColumnType::Text);
+
+    // Empty values don't change the inferred type
+    assert_eq!(column_type_of(&["", "12", ""]), ColumnType::Integer);
+    assert_eq!(column_type_of(&["", ""]), ColumnType::Varchar(1));
+}
+
+#[tokio::test]
+async fn test_varchar_width_in_characters() {
+    // Accented and CJK values are sized by their characters, not by their UTF-8 bytes
+    assert_eq!(column_type_of(&["café", "naïve"]), ColumnType::Varchar(6));
+    assert_eq!(column_type_of(&["東京都"]), ColumnType::Varchar(4));
+    assert_eq!(column_type_of(&[&"é".repeat(200)]), ColumnType::Varchar(201));
+
+    let profile: ColumnProfile = profile_of(&["東京都", "abcd"]);
+    assert_eq!(profile.max_chars, 4);
+    assert_eq!(profile.max_bytes, 9);
+
+    // The unit, the threshold and the padding can be changed
+    let bytes: VarcharSizing = VarcharSizing::default().with_overrides(Some(LengthUnit::Bytes), None, Some(0));
+    assert_eq!(profile.column_type(&bytes), ColumnType::Varchar(9));
+    let narrow: VarcharSizing = VarcharSizing::default().with_overrides(None, Some(4), None);
+    assert_eq!(profile.column_type(&narrow), ColumnType::Text);
+    assert_eq!(column_type_of(&[""]), ColumnType::Varchar(1));
+    assert_eq!(profile_of(&[""]).column_type(&bytes), ColumnType::Varchar(1));
+
+    // A wider VARCHAR has to be asked for
+    let wide: ColumnProfile = profile_of(&[&"a".repeat(500)]);
+    assert_eq!(wide.column_type(&VarcharSizing::default()), ColumnType::Text);
+    let widened: VarcharSizing = VarcharSizing::default().with_overrides(None, Some(1000), None);
+    assert_eq!(wide.column_type(&widened), ColumnType::Varchar(501));
+}
+
+#[tokio::test]
+async fn test_column_type_fits_in() {
+    // An untyped or unbounded text column accepts every value
+    assert!(ColumnType::Integer.fits_in("character varying", None));
+    assert!(ColumnType::Text.fits_in("longtext", None));
+    assert!(ColumnType::Timestamp.fits_in("", None));
+
+    assert!(ColumnType::Integer.fits_in("BIGINT", None));
+    assert!(ColumnType::Integer.fits_in("numeric", None));
+    assert!(!ColumnType::BigInt.fits_in("integer", None));
+    assert!(ColumnType::Boolean.fits_in("tinyint", None));
+    assert!(ColumnType::Date.fits_in("timestamp without time zone", None));
+    assert!(ColumnType::Timestamp.fits_in("datetime", None));
+    assert!(!ColumnType::Timestamp.fits_in("date", None));
+    assert!(!ColumnType::Decimal {
+        precision: 4,
+        scale: 2
+    }
+    .fits_in("int", None));
+    assert!(!ColumnType::Text.fits_in("integer", None));
+
+    // Type names are matched exactly, not by substring
+    assert!(!ColumnType::Integer.fits_in("interval", None));
+    assert!(!ColumnType::Integer.fits_in("point", None));
+
+    // An i32 value does not fit in the narrow integer columns
+    assert!(!ColumnType::Integer.fits_in("tinyint", None));
+    assert!(!ColumnType::Integer.fits_in("smallint", None));
+    assert!(!ColumnType::Integer.fits_in("mediumint", None));
+    assert!(!ColumnType::Integer.fits_in("numeric(5,0)", None));
+    assert!(!ColumnType::BigInt.fits_in("decimal(12)", None));
+
+    // The precision and the scale of a decimal column are compared
+    let decimal: ColumnType = ColumnType::Decimal {
+        precision: 6,
+        scale: 2,
+    };
+    assert!(decimal.fits_in("DECIMAL(10,2)", None));
+    assert!(!decimal.fits_in("numeric(10,1)", None));
+    assert!(!decimal.fits_in("numeric(5,2)", None));
+
+    // The length of a character column comes from the catalog or from the declared type
+    assert!(ColumnType::Varchar(20).fits_in("character varying", Some(20)));
+    assert!(!ColumnType::Varchar(40).fits_in("character varying", Some(20)));
+    assert!(!ColumnType::Varchar(40).fits_in("VARCHAR(20)", None));
+    assert!(!ColumnType::Text.fits_in("varchar", Some(255)));
+    assert!(ColumnType::Integer.fits_in("char", Some(11)));
+    assert!(!ColumnType::BigInt.fits_in("char", Some(11)));
+    assert!(!ColumnType::Varchar(300).fits_in("tinytext", None));
+}
@@ -1,5 +1,9 @@
-use crate::fileflow::utils::csv_utils::{find_separator, read_first_line};
-use crate::tests::utils_tests::{generate_csv_file, remove_csv_file};
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::stuct::csv_dialect::CsvDialect;
+use crate::fileflow::utils::csv_utils::{get_file_fingerprint, read_column_names, sniff_dialect};
+use crate::tests::utils_tests::{generate_csv_file_with_content, remove_csv_file};
+use csv::Reader;
+use std::fs::File;
 
 #[tokio::test]
 async fn test_detect_separator() {
@@ -14,18 +18,148 @@ async fn test_detect_separator() {
     ];
 
     for (input, expected) in test_cases {
+        let csv_file_path: String =
+            generate_csv_file_with_content("test_detect_separator", &format!("{input}\n"))
+                .expect("Failed to generate csv file");
         match expected {
-            Some(separator) => assert_eq!(find_separator(input).unwrap(), separator),
-            None => assert!(find_separator(input).is_err()),
+            Some(separator) => {
+                assert_eq!(sniff_dialect(&csv_file_path).unwrap().separator, separator)
+            }
+            None => assert!(sniff_dialect(&csv_file_path).is_err()),
         }
     }
+    remove_csv_file("test_detect_separator").expect("Failed to remove csv file");
 }
 
 #[tokio::test]
-async fn test_read_first_line() {
+async fn test_sniff_dialect() {
+    // Spaces inside the headers don't win over the separator that splits every line the same way
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_sniff_dialect",
+        "first name;last name;age\nJohn;Doe;42\nJane Ann;Roe;37\n",
+    )
+    .expect("Failed to generate csv file");
+    assert_eq!(
+        sniff_dialect(&csv_file_path).unwrap(),
+        CsvDialect {
+            separator: ';',
+            quote: '"',
+            escape: None,
+            has_headers: true,
+        }
+    );
+
+    // Separators inside quoted fields are ignored, quotes are escaped with a backslash
+    std::fs::write(
+        &csv_file_path,
+        "'city, state'|'code'\n'Austin, TX'|'say \\'hi\\''\n'Boston, MA'|'b'\n",
+    )
+    .expect("Failed to write csv file");
+    assert_eq!(
+        sniff_dialect(&csv_file_path).unwrap(),
+        CsvDialect {
+            separator: '|',
+            quote: '\'',
+            escape: Some('\\'),
+            has_headers: true,
+        }
+    );
+
+    // A quoted field spanning past the first lines is sampled whole, its inner lines aren't records
+    std::fs::write(
+        &csv_file_path,
+        format!("a|b|c\n1|\"first\n{}last\"|3\n", "p,q,r\n".repeat(120)),
+    )
+    .expect("Failed to write csv file");
+    assert_eq!(sniff_dialect(&csv_file_path).unwrap().separator, '|');
+
+    // A first row of numbers above columns of numbers is data
+    std::fs::write(&csv_file_path, "1,2.5,a\n3,4.5,b\n").expect("Failed to write csv file");
+    assert!(!sniff_dialect(&csv_file_path).unwrap().has_headers);
+
+    std::fs::write(&csv_file_path, "").expect("Failed to write csv file");
+    assert!(sniff_dialect(&csv_file_path).is_err());
+
+    remove_csv_file("test_sniff_dialect").expect("Failed to remove csv file");
+}
+
+#[tokio::test]
+async fn test_file_fingerprint() {
     let csv_file_path: String =
-        generate_csv_file("test_read_first_line").expect("Failed to generate csv file");
-    let first_line: String = read_first_line(&csv_file_path).expect("Failed to read first line");
-    assert_eq!(first_line, "header1,header2");
-    remove_csv_file("test_read_first_line").expect("Failed to remove csv file");
+        generate_csv_file_with_content("test_file_fingerprint", "header1,header2\nvalue1,value2\n")
+            .expect("Failed to generate csv file");
+
+    let fingerprint: String =
+        get_file_fingerprint(&csv_file_path).expect("Failed to fingerprint file");
+    assert_eq!(
+        fingerprint,
+        "57bab3fee5406989fab22059700bd5ae3124eb107c285e4e0d466cdbf1df215c"
+    );
+
+    // A change of the last line of a large file, which keeps its size, is detected
+    let lines: String = "value1,value2\n".repeat(10_000);
+    std::fs::write(&csv_file_path, format!("{lines}value1,value2\n"))
+        .expect("Failed to write csv file");
+    let fingerprint: String =
+        get_file_fingerprint(&csv_file_path).expect("Failed to fingerprint file");
+    std::fs::write(&csv_file_path, format!("{lines}value1,value3\n"))
+        .expect("Failed to write csv file");
+    assert_ne!(
+        get_file_fingerprint(&csv_file_path).expect("Failed to fingerprint file"),
+        fingerprint
+    );
+    assert!(get_file_fingerprint("missing_file.csv").is_err());
+
+    remove_csv_file("test_file_fingerprint").expect("Failed to remove csv file");
+}
+
+#[tokio::test]
+async fn test_read_column_names() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_read_column_names",
+        "\"City, State\",\"Zip\ncode\",\nAustin,78701,x\n",
+    )
+    .expect("Failed to generate csv file");
+
+    // Quoted separators and line breaks stay in their column, an empty header is named after its position
+    let mut reader: Reader<File> = CsvDialect::default()
+        .get_reader_builder()
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    assert_eq!(
+        read_column_names(&mut reader, true, &[], &DatabaseEngine::Postgres).unwrap(),
+        vec!["city,_state", "zip\ncode", "column_3"]
+    );
+    assert_eq!(reader.records().count(), 1);
+
+    // Without a header row the first record is kept as data
+    let mut reader: Reader<File> = CsvDialect {
+        has_headers: false,
+        ..CsvDialect::default()
+    }
+    .get_reader_builder()
+    .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    assert_eq!(
+        read_column_names(&mut reader, false, &[], &DatabaseEngine::Postgres).unwrap(),
+        vec!["column_1", "column_2", "column_3"]
+    );
+    assert_eq!(reader.records().count(), 2);
+
+    // Given column names replace the header row, they must match its width
+    let mut reader: Reader<File> = CsvDialect::default()
+        .get_reader_builder()
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let column_names: Vec<String> = vec!["Town".into(), "Postal Code".into(), String::new()];
+    assert_eq!(
+        read_column_names(&mut reader, true, &column_names, &DatabaseEngine::Postgres).unwrap(),
+        vec!["town", "postal_code", "column_3"]
+    );
+    assert!(read_column_names(
+        &mut reader,
+        true,
+        &column_names[..2],
+        &DatabaseEngine::Postgres
+    )
+    .is_err());
+
+    remove_csv_file("test_read_column_names").expect("Failed to remove csv file");
 }
@@ -1,15 +1,22 @@
 use crate::fileflow::database::connection::{Connection, QueryResult};
-use crate::fileflow::database::database_actions::export_table;
+use crate::fileflow::database::database_actions::{
+    batch_insert, copy_from_stdin, export_table, get_warning_count,
+};
 use crate::fileflow::enumeration::separator::SeparatorType;
+use crate::fileflow::stuct::cancel_token::CancelToken;
 use crate::fileflow::stuct::db_config::DbConfig;
 use crate::fileflow::stuct::download_config::DownloadConfig;
+use crate::fileflow::stuct::export_report::TableExport;
+use crate::fileflow::stuct::progress::Progress;
 use crate::tests::utils_tests::{
     create_test_db, get_test_maridb_config, get_test_mysql_config, get_test_pg_config,
     get_test_sqlite_config, remove_test_db,
 };
+use csv::StringRecord;
 use sqlx::testing::TestTermination;
 use sqlx::{Error, Row};
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 #[tokio::test]
 async fn test_get_connection_url() {
@@ -125,6 +132,8 @@ async fn test_download_table() {
         separator: SeparatorType::Semicolon,
         table_name_list: vec!["test_table".into()],
         location: "./".into(),
+        job_id: String::new(),
+        schema: Some("main".into()),
     };
 
     let file_path: PathBuf = PathBuf::from(format!(
@@ -132,11 +141,30 @@ async fn test_download_table() {
         download_config.location, download_config.table_name_list[0]
     ));
 
-    export_table(&conn, &download_config, &download_config.table_name_list[0])
-        .await
-        .expect("Failed to export table");
+    let progress: Mutex<Vec<Progress>> = Mutex::new(Vec::new());
+    let progress_listener = |p: &Progress| progress.lock().unwrap().push(p.clone());
+    let exported: TableExport = export_table(
+        &conn,
+        &download_config,
+        &download_config.table_name_list[0],
+        &progress_listener,
+        &CancelToken::default(),
+    )
+    .await
+    .expect("Failed to export table");
 
     assert!(std::path::Path::new(&file_path).exists()); // check if file exists
+    assert_eq!(exported.table_name, "test_table");
+    assert_eq!(exported.file_path, file_path.to_string_lossy());
+    assert_eq!(exported.rows_exported, 2);
+    assert_eq!(exported.bytes_written, 44);
+
+    // The progress is reported once per page of exported rows
+    let reported: Vec<Progress> = progress.lock().unwrap().clone();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(reported[0].table_name, "test_table");
+    assert_eq!(reported[0].rows_processed, 2);
+    assert_eq!(reported[0].estimated_remaining_seconds, None);
 
     // get the content of the file
     let content: String = std::fs::read_to_string(&file_path).expect("Failed to read file");
@@ -149,11 +177,19 @@ async fn test_download_table() {
         separator: SeparatorType::Comma,
         table_name_list: vec!["test_table".into()],
         location: "./".into(),
+        job_id: String::new(),
+        schema: None,
     };
 
-    export_table(&conn, &download_config, &download_config.table_name_list[0])
-        .await
-        .expect("Failed to export table");
+    export_table(
+        &conn,
+        &download_config,
+        &download_config.table_name_list[0],
+        &|_: &Progress| {},
+        &CancelToken::default(),
+    )
+    .await
+    .expect("Failed to export table");
 
     // check if file exists
     assert!(std::path::Path::new(&file_path).exists());
@@ -165,8 +201,125 @@ async fn test_download_table() {
         "Failed to export table"
     );
 
+    // A cancelled export leaves no partial file behind
+    std::fs::remove_file(&file_path).expect("Failed to remove file");
+    let cancel_token: CancelToken = CancelToken::default();
+    cancel_token.cancel();
+    let result: Result<TableExport, Box<dyn std::error::Error>> = export_table(
+        &conn,
+        &download_config,
+        &download_config.table_name_list[0],
+        &|_: &Progress| {},
+        &cancel_token,
+    )
+    .await;
+    assert_eq!(result.unwrap_err().to_string(), "Export cancelled");
+    assert!(!std::path::Path::new(&file_path).exists());
 
     // Clean up
-    std::fs::remove_file(&file_path).expect("Failed to remove file");
     remove_test_db("test_download_table").expect("Failed to remove test table");
 }
+
+#[tokio::test]
+async fn test_batch_insert_splits_on_bind_limit() {
+    let file_path: String = create_test_db("test_batch_insert_split");
+    let config: DbConfig = get_test_sqlite_config(file_path);
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    const SQL_ARRAY: [&str; 2] = [
+        "DROP TABLE IF EXISTS test_table",
+        "CREATE TABLE test_table (header1 TEXT, header2 TEXT)",
+    ];
+
+    for sql in SQL_ARRAY.iter() {
+        conn.query(sql).await.expect("Failed to prepare the table");
+    }
+
+    // 20 000 rows of 2 values are above the 32 766 parameters allowed by SQLite
+    let batch: Vec<StringRecord> = (0..20_000)
+        .map(|i| StringRecord::from(vec![format!("value{i}"), "it's".into()]))
+        .collect();
+    let statements: Vec<&[StringRecord]> = conn.get_batch_limits().split(&batch);
+    assert_eq!(statements.len(), 2);
+
+    for rows in statements {
+        batch_insert(
+            &conn,
+            "INSERT INTO test_table (header1, header2) ",
+            rows,
+            &[],
+            "",
+            "Failed to insert batch data",
+        )
+        .await
+        .expect("Failed to insert batch");
+    }
+
+    let query_result: QueryResult = conn
+        .query_many_with_result("SELECT COUNT(*) AS total FROM test_table WHERE header2 = 'it''s'")
+        .await
+        .expect("Failed to count rows");
+
+    if let QueryResult::SQLite(rows) = query_result {
+        let total: i64 = rows[0].get("total");
+        assert_eq!(total, 20_000);
+    }
+
+    conn.disconnect();
+    drop(conn);
+    remove_test_db("test_batch_insert_split").expect("Failed to remove test table");
+}
+
+#[tokio::test]
+async fn test_copy_from_stdin_requires_postgres() {
+    let file_path: String = create_test_db("test_copy_from_stdin");
+    let config: DbConfig = get_test_sqlite_config(file_path);
+    let conn: Connection = Connection::connect(&config)
+        .await
+        .expect("Failed to connect to the database");
+
+    let result: Result<u64, String> = copy_from_stdin(
+        &conn,
+        "COPY \"t\" FROM STDIN (FORMAT csv)",
+        std::io::empty(),
+        |_| Ok(()),
+    )
+    .await;
+    assert_eq!(
+        result.unwrap_err(),
+        "COPY FROM STDIN is only available for Postgres"
+    );
+
+    conn.disconnect();
+    drop(conn);
+    remove_test_db("test_copy_from_stdin").expect("Failed to remove test table");
+}
+
+#[tokio::test]
+async fn test_session_connection() {
+    let file_path: String = create_test_db("test_session_connection");
+    let config: DbConfig = get_test_sqlite_config(file_path);
+    let conn: Connection = Connection::connect_session(&config)
+        .await
+        .expect("Failed to connect to the database");
+    assert!(!conn.is_transactional());
+
+    // A temporary table only exists in the session that created it
+    conn.query("CREATE TEMP TABLE session_table (id INTEGER)")
+        .await
+        .expect("Failed to create the temporary table");
+    conn.query("INSERT INTO session_table VALUES (1)")
+        .await
+        .expect("Statements must run on the same session");
+
+    assert_eq!(
+        get_warning_count(&conn).await.unwrap_err(),
+        "Warnings are only counted for MySQL and MariaDB"
+    );
+
+    conn.disconnect();
+    drop(conn);
+    remove_test_db("test_session_connection").expect("Failed to remove test table");
+}
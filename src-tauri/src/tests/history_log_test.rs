@@ -0,0 +1,90 @@
+use crate::fileflow::enumeration::history_operation::HistoryOperation;
+use crate::fileflow::enumeration::history_outcome::HistoryOutcome;
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::stuct::history_entry::HistoryEntry;
+use crate::fileflow::stuct::history_filter::HistoryFilter;
+use crate::fileflow::stuct::history_log::HistoryLog;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_history_log() {
+    let history: HistoryLog = HistoryLog::new("test_history_log.jsonl");
+    history.clear().expect("Failed to clear an empty history");
+    assert!(history
+        .list(&HistoryFilter::default())
+        .expect("Failed to read an empty history")
+        .is_empty());
+
+    let imported: HistoryEntry = HistoryEntry::new(
+        HistoryOperation::Import,
+        "data/cities.csv",
+        Some("42-1700000000-00000000deadbeef".into()),
+        "production",
+        vec!["staging.cities".into()],
+        Some(InsertionType::Fast),
+    )
+    .with_import_result(
+        &Err("Error: Failed to insert data: disk full".into()),
+        Duration::from_millis(1500),
+    );
+    let cancelled: HistoryEntry = HistoryEntry::new(
+        HistoryOperation::Export,
+        "exports",
+        None,
+        "production",
+        vec!["cities".into(), "countries".into()],
+        None,
+    )
+    .with_export_result(
+        &Err("Cancelled: exported 1 out of 2 tables before the export was cancelled.".into()),
+        Duration::ZERO,
+    );
+    history.append(&imported).expect("Failed to append entry");
+    history.append(&cancelled).expect("Failed to append entry");
+
+    assert_eq!(imported.outcome, HistoryOutcome::Failed);
+    assert_eq!(imported.duration_ms, 1500);
+    assert_eq!(cancelled.outcome, HistoryOutcome::Cancelled);
+
+    // The most recent entry comes first
+    let entries: Vec<HistoryEntry> = history
+        .list(&HistoryFilter::default())
+        .expect("Failed to read the history");
+    assert_eq!(entries, vec![cancelled.clone(), imported.clone()]);
+
+    let by_table: HistoryFilter = HistoryFilter {
+        table_name: Some("STAGING.CITIES".into()),
+        ..HistoryFilter::default()
+    };
+    assert_eq!(
+        history.list(&by_table).expect("Failed to read the history"),
+        vec![imported.clone()]
+    );
+
+    let by_operation: HistoryFilter = HistoryFilter {
+        operation: Some(HistoryOperation::Export),
+        limit: Some(1),
+        ..HistoryFilter::default()
+    };
+    assert_eq!(
+        history
+            .list(&by_operation)
+            .expect("Failed to read the history"),
+        vec![cancelled]
+    );
+
+    let in_the_future: HistoryFilter = HistoryFilter {
+        since: Some(imported.timestamp + 3600),
+        ..HistoryFilter::default()
+    };
+    assert!(history
+        .list(&in_the_future)
+        .expect("Failed to read the history")
+        .is_empty());
+
+    history.clear().expect("Failed to clear the history");
+    assert!(history
+        .list(&HistoryFilter::default())
+        .expect("Failed to read the history")
+        .is_empty());
+}
@@ -1,10 +1,16 @@
 #[cfg(test)]
 mod action_test;
 #[cfg(test)]
+mod column_profile_test;
+#[cfg(test)]
 mod csv_utils_test;
 #[cfg(test)]
 mod database_test;
 #[cfg(test)]
+mod history_log_test;
+#[cfg(test)]
+mod reject_writer_test;
+#[cfg(test)]
 mod string_formater_test;
 #[cfg(test)]
 mod struct_test;
@@ -0,0 +1,124 @@
+use crate::fileflow::enumeration::reject_format::RejectFormat;
+use crate::fileflow::stuct::file_section::FileSection;
+use crate::fileflow::stuct::import_context::ImportContext;
+use crate::fileflow::stuct::reject_writer::RejectWriter;
+use crate::tests::utils_tests::{generate_csv_file_with_content, remove_csv_file};
+use csv::{Reader, ReaderBuilder, StringRecord};
+use std::fs::File;
+
+#[tokio::test]
+async fn test_reject_record_to_csv() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_reject_record_to_csv",
+        "header1;header2\nvalue1;value2\nvalue;with, comma\n",
+    )
+    .expect("Failed to generate csv file");
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let records: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+
+    let mut rejects: RejectWriter = RejectWriter::new(&csv_file_path, ';', RejectFormat::Csv);
+    assert!(rejects
+        .get_reject_path()
+        .ends_with("test_reject_record_to_csv.rejected.csv"));
+    rejects
+        .flush()
+        .expect("Failed to flush an empty reject file");
+    assert!(!std::path::Path::new(rejects.get_reject_path()).exists());
+
+    rejects
+        .reject_record(&records[1], "Failed to insert row: constraint failed")
+        .expect("Failed to reject record");
+    rejects.flush().expect("Failed to flush reject file");
+    assert_eq!(rejects.get_rejected_count(), 1);
+
+    let content: String =
+        std::fs::read_to_string(rejects.get_reject_path()).expect("Failed to read reject file");
+    assert_eq!(
+        content,
+        "line,error,raw\n3,Failed to insert row: constraint failed,\"value;with, comma\"\n"
+    );
+
+    std::fs::remove_file(rejects.get_reject_path()).expect("Failed to remove reject file");
+    remove_csv_file("test_reject_record_to_csv").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_reject_malformed_to_ndjson() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_reject_malformed_to_ndjson",
+        "header1,header2\nvalue1,value2\nvalue3\r\nvalue4,value5\n",
+    )
+    .expect("Failed to generate csv file");
+
+    let mut reader: Reader<File> = ReaderBuilder::new()
+        .from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let error: csv::Error = reader
+        .records()
+        .find_map(|r| r.err())
+        .expect("The short record must be malformed");
+
+    let mut rejects: RejectWriter = RejectWriter::new(&csv_file_path, ',', RejectFormat::Ndjson);
+    rejects
+        .reject_malformed(&error)
+        .expect("Failed to reject record");
+    rejects.flush().expect("Failed to flush reject file");
+
+    let content: String =
+        std::fs::read_to_string(rejects.get_reject_path()).expect("Failed to read reject file");
+    let entry: serde_json::Value =
+        serde_json::from_str(content.trim_end()).expect("Failed to parse reject entry");
+    assert_eq!(entry["line"], 3);
+    assert_eq!(entry["raw"], "value3");
+    assert_eq!(entry["error"], error.to_string());
+
+    std::fs::remove_file(rejects.get_reject_path()).expect("Failed to remove reject file");
+    remove_csv_file("test_reject_malformed_to_ndjson").expect("Failed to remove CSV file");
+}
+
+#[tokio::test]
+async fn test_reject_malformed_in_file_section() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_reject_malformed_in_file_section",
+        "Exported on 2024-01-01\nid,name\n1,a\n2\n3,c\nTotal: 3 rows\n",
+    )
+    .expect("Failed to generate csv file");
+
+    let section: FileSection =
+        FileSection::open(&csv_file_path, 1, 1).expect("Failed to open file section");
+    let mut context: ImportContext =
+        ImportContext::new(&csv_file_path, ',', RejectFormat::Ndjson).with_file_section(&section);
+    let section_length: u64 = section.len();
+
+    let mut reader: Reader<FileSection> = Reader::from_reader(section);
+    let error: csv::Error = reader
+        .records()
+        .find_map(|r| r.err())
+        .expect("The short record must be malformed");
+
+    // The line number and the raw text are those of the whole file, not of the section
+    context
+        .rejects
+        .reject_malformed(&error)
+        .expect("Failed to reject record");
+    context
+        .rejects
+        .flush()
+        .expect("Failed to flush reject file");
+
+    let content: String = std::fs::read_to_string(context.rejects.get_reject_path())
+        .expect("Failed to read reject file");
+    let entry: serde_json::Value =
+        serde_json::from_str(content.trim_end()).expect("Failed to parse reject entry");
+    assert_eq!(entry["line"], 4);
+    assert_eq!(entry["raw"], "2");
+
+    // The progress is measured against the section
+    context.report_completed("test_table", 2);
+    assert_eq!(context.get_bytes_processed(), section_length);
+
+    std::fs::remove_file(context.rejects.get_reject_path()).expect("Failed to remove reject file");
+    remove_csv_file("test_reject_malformed_in_file_section").expect("Failed to remove CSV file");
+}
@@ -1,30 +1,5 @@
+use crate::fileflow::enumeration::database_engine::DatabaseEngine;
 use crate::fileflow::stuct::string_formater::{StringFormatter};
-use csv::StringRecord;
-
-#[tokio::test]
-async fn test_escape_values() {
-    let record: StringRecord = StringRecord::from(vec!["value1", "value2"]);
-    let values: String = StringFormatter::escaped_record(record);
-    assert_eq!(values, "'value1', 'value2'");
-
-    let record: StringRecord = StringRecord::from(vec![
-        "\"INSERT INTO test_table VALUES (1,2);\"",
-        "UPDATE test_table SET column1 = 1;",
-        "DELETE FROM test_table WHERE column1 = 1;",
-        "SELECT * FROM test_table;",
-    ]);
-    let values: String = StringFormatter::escaped_record(record);
-    assert_eq!(values, "'INSERT INTO test_table VALUES (1,2);', 'UPDATE test_table SET column1 = 1;', 'DELETE FROM test_table WHERE column1 = 1;', 'SELECT * FROM test_table;'");
-}
-
-#[tokio::test]
-async fn test_sanitize_value() {
-    assert_eq!(StringFormatter::sanitize_value("value1"), "value1");
-    assert_eq!(StringFormatter::sanitize_value("'value1'"), "''value1''");
-    assert_eq!(StringFormatter::sanitize_value("value1'"), "value1''");
-    assert_eq!(StringFormatter::sanitize_value("value1\\value2"), "value1\\\\value2");
-    assert_eq!(StringFormatter::sanitize_value("value1\"value2"), "value1value2");
-}
 
 #[tokio::test]
 async fn test_sanitize_column() {
@@ -51,16 +26,60 @@ async fn test_sanitize_column() {
 
 #[tokio::test]
 async fn test_get_formated_column_names() {
+    let postgres: &DatabaseEngine = &DatabaseEngine::Postgres;
     assert_eq!(
-        StringFormatter::get_formated_column_names(&vec!["header 1".into(), " header2".into()]),
+        StringFormatter::get_formated_column_names(&["header 1".into(), " header2".into()], postgres),
         vec!["header_1", "header2"]
     );
     assert_eq!(
-        StringFormatter::get_formated_column_names(&vec!["header    1".into(), String::new(), "header2".into(), "".into()]),
+        StringFormatter::get_formated_column_names(&["header    1".into(), String::new(), "header2".into(), "".into()], postgres),
         vec!["header____1", "column_2", "header2", "column_4"]
     );
     assert_eq!(
-        StringFormatter::get_formated_column_names(&vec!["header'\" 1".into(), "header 2''\\".into()]),
+        StringFormatter::get_formated_column_names(&["header'\" 1".into(), "header 2''\\".into()], postgres),
         vec!["header_1", "header_2"]
     );
+
+    // Collisions once sanitized are suffixed, even with a generated name
+    assert_eq!(
+        StringFormatter::get_formated_column_names(&["Name".into(), "name".into(), "a b".into(), "a_b".into(), "name_2".into()], postgres),
+        vec!["name", "name_2", "a_b", "a_b_2", "name_2_2"]
+    );
+    assert_eq!(
+        StringFormatter::get_formated_column_names(&["column_2".into(), String::new()], postgres),
+        vec!["column_2", "column_2_2"]
+    );
+
+    // Reserved words, leading digits and long names
+    assert_eq!(
+        StringFormatter::get_formated_column_names(&["Order".into(), "2024 sales".into(), "user".into()], postgres),
+        vec!["order_", "_2024_sales", "user_"]
+    );
+    let long_name: String = "a".repeat(70);
+    assert_eq!(
+        StringFormatter::get_formated_column_names(&[long_name.clone(), long_name.clone()], postgres),
+        vec!["a".repeat(63), format!("{}_2", "a".repeat(61))]
+    );
+    assert_eq!(
+        StringFormatter::get_formated_column_names(std::slice::from_ref(&long_name), &DatabaseEngine::MySQL),
+        vec!["a".repeat(64)]
+    );
+    assert_eq!(
+        StringFormatter::get_formated_column_names(std::slice::from_ref(&long_name), &DatabaseEngine::SQLite),
+        vec![long_name]
+    );
+    assert_eq!(
+        StringFormatter::format_column_name(&format!("{}é", "a".repeat(62)), postgres),
+        "a".repeat(62)
+    );
+}
+
+#[tokio::test]
+async fn test_similarity() {
+    assert_eq!(StringFormatter::similarity("amount", "amount"), 1.0);
+    assert_eq!(StringFormatter::similarity("", ""), 1.0);
+    assert_eq!(StringFormatter::similarity("abc", "xyz"), 0.0);
+    assert_eq!(StringFormatter::similarity("price", "prices"), 1.0 - 1.0 / 6.0);
+    assert!(StringFormatter::similarity("customer_name", "customer_full_name") > 0.7);
+    assert!(StringFormatter::similarity("id", "ip") < 0.7);
 }
@@ -1,14 +1,34 @@
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
 use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::enumeration::length_unit::LengthUnit;
+use crate::fileflow::enumeration::optimized_strategy::OptimizedStrategy;
+use crate::fileflow::enumeration::reject_format::RejectFormat;
 use crate::fileflow::enumeration::separator::SeparatorType;
+use crate::fileflow::stuct::batch_limits::BatchLimits;
 use crate::fileflow::stuct::db_config::DbConfig;
 use crate::fileflow::stuct::download_config::DownloadConfig;
+use crate::fileflow::stuct::file_section::FileSection;
+use crate::fileflow::stuct::import_checkpoint::ImportCheckpoint;
+use crate::fileflow::stuct::import_context::ImportContext;
 use crate::fileflow::stuct::insert_config::InsertConfig;
+use crate::fileflow::stuct::job_registry::{JobGuard, JobRegistry};
+use crate::fileflow::stuct::progress::Progress;
 use crate::fileflow::stuct::save_config::SaveConfig;
+use crate::fileflow::stuct::varchar_sizing::VarcharSizing;
+use crate::tests::utils_tests::{
+    generate_csv_file, generate_csv_file_with_content, remove_csv_file,
+};
+use csv::{Reader, StringRecord};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_db_config() {
-    let config = DbConfig {
+    let mut config = DbConfig {
+        config_name: String::new(),
         db_driver: DatabaseEngine::Postgres,
         username: String::from("username"),
         password: String::from("password"),
@@ -25,6 +45,14 @@ async fn test_db_config() {
     assert_eq!(config.port, "port");
     assert_eq!(config.db_name, "db_name");
     assert_eq!(config.sqlite_file_path, "sqlite_file_path");
+
+    // The history names the connection without its password
+    assert_eq!(
+        config.get_connection_name(),
+        "username@db_host:port/db_name"
+    );
+    config.config_name = "production".into();
+    assert_eq!(config.get_connection_name(), "production");
 }
 
 #[tokio::test]
@@ -32,14 +60,49 @@ async fn test_insert_config() {
     let config = InsertConfig {
         file_path: "file_path".into(),
         table_name: "table_name".into(),
+        schema: Some("staging".into()),
         mode: InsertionType::Fast,
         db_driver: DatabaseEngine::Postgres,
+        key_columns: vec!["id".into()],
+        conflict_policy: ConflictPolicy::Update,
+        bulk_load: false,
+        transactional: false,
+        reject_format: RejectFormat::Csv,
+        job_id: String::new(),
+        workers: 4,
+        has_headers: None,
+        column_names: Vec::new(),
+        preamble_lines: 0,
+        footer_lines: 0,
+        varchar_length_unit: None,
+        varchar_max_length: Some(1000),
+        varchar_padding: None,
+        optimized_strategy: None,
+        add_new_columns: false,
     };
 
     assert_eq!(config.db_driver, DatabaseEngine::Postgres);
     assert_eq!(config.file_path, "file_path");
     assert_eq!(config.table_name, "table_name");
     assert_eq!(config.mode, InsertionType::Fast);
+    assert_eq!(config.key_columns, vec!["id"]);
+    assert_eq!(config.conflict_policy, ConflictPolicy::Update);
+    assert!(config.is_resumable());
+    // The COPY into a new Postgres table has no batch to resume from
+    assert!(!config.is_checkpointed());
+    assert_eq!(config.get_qualified_table_name(), "staging.table_name");
+    assert_eq!(
+        config.get_varchar_sizing(),
+        VarcharSizing {
+            unit: LengthUnit::Characters,
+            max_length: 1000,
+            padding: 1,
+        }
+    );
+    assert_eq!(
+        config.get_optimized_strategy(),
+        OptimizedStrategy::AlterInPlace
+    );
 }
 
 #[tokio::test]
@@ -70,6 +133,8 @@ async fn test_downlopad_config() {
         table_name_list: vec!["table1".into(), "table2".into()],
         location: "location".into(),
         separator: SeparatorType::Comma,
+        job_id: "job_id".into(),
+        schema: None,
     };
 
     assert_eq!(config.table_name_list.len(), 2);
@@ -77,4 +142,161 @@ async fn test_downlopad_config() {
     assert_eq!(config.table_name_list[1], "table2");
     assert_eq!(config.location, "location");
     assert_eq!(config.separator, SeparatorType::Comma);
+    assert_eq!(config.job_id, "job_id");
+}
+
+#[tokio::test]
+async fn test_progress_estimation() {
+    let progress: Progress = Progress::new("table_name", 250, 1_000, 10, 1, Duration::from_secs(5));
+    assert_eq!(progress.bytes_read, 250);
+    assert_eq!(progress.estimated_remaining_seconds, Some(15));
+
+    let progress: Progress = Progress::new("table_name", 0, 1_000, 0, 0, Duration::from_secs(5));
+    assert_eq!(progress.estimated_remaining_seconds, None);
+
+    let progress: Progress =
+        Progress::new("table_name", 2_000, 1_000, 10, 0, Duration::from_secs(5));
+    assert_eq!(progress.bytes_read, 1_000);
+    assert_eq!(progress.estimated_remaining_seconds, Some(0));
+}
+
+#[tokio::test]
+async fn test_import_context_reports_progress() {
+    let csv_file_path: String =
+        generate_csv_file("test_import_context_progress").expect("Failed to generate csv file");
+    let reported: Arc<Mutex<Vec<Progress>>> = Arc::new(Mutex::new(Vec::new()));
+    let listener_reported: Arc<Mutex<Vec<Progress>>> = Arc::clone(&reported);
+
+    let mut context: ImportContext = ImportContext::new(&csv_file_path, ',', RejectFormat::Csv)
+        .with_progress_listener(Box::new(move |p: &Progress| {
+            listener_reported.lock().unwrap().push(p.clone())
+        }));
+
+    let mut reader: Reader<File> =
+        Reader::from_reader(File::open(&csv_file_path).expect("Failed to open CSV file"));
+    let batch: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+
+    context.report_batch("table_name", &batch[..1], 1);
+    assert_eq!(context.get_bytes_processed(), 16);
+    context.report_completed("table_name", 2);
+
+    let reported: Vec<Progress> = reported.lock().unwrap().clone();
+    assert_eq!(reported.len(), 2);
+    assert_eq!(reported[0].bytes_read, 16); // Position of the first record, right after the header
+    assert_eq!(reported[0].rows_processed, 1);
+    assert_eq!(reported[1].bytes_read, reported[1].total_bytes);
+    assert_eq!(reported[1].rows_processed, 2);
+    assert_eq!(reported[1].estimated_remaining_seconds, Some(0));
+    let total_bytes: u64 = reported[1].total_bytes;
+    assert_eq!(context.get_bytes_processed(), total_bytes);
+
+    // A resumed import only counts the bytes read past its checkpoint
+    let mut checkpoint: ImportCheckpoint =
+        ImportCheckpoint::new(&csv_file_path, "fingerprint", "table_name");
+    checkpoint.set_position(batch[0].position().expect("Missing position"), 1);
+    let mut context: ImportContext =
+        ImportContext::new(&csv_file_path, ',', RejectFormat::Csv).with_checkpoint(checkpoint);
+    context.report_completed("table_name", 1);
+    assert_eq!(context.get_bytes_processed(), total_bytes - 16);
+    assert_eq!(context.get_resumed_rows(), 1);
+
+    remove_csv_file("test_import_context_progress").expect("Failed to remove csv file");
+}
+
+#[tokio::test]
+async fn test_job_registry_cancel() {
+    let registry: JobRegistry = JobRegistry::default();
+    assert!(!registry.cancel("job_id"));
+
+    {
+        let job: JobGuard = registry.register("job_id").expect("Failed to register job");
+        assert!(!job.cancel_token.is_cancelled());
+        // Another job can't share its id while it runs
+        assert!(registry.register("job_id").is_err());
+        assert!(registry.cancel("job_id"));
+        assert!(job.cancel_token.is_cancelled());
+    }
+
+    // The job is unregistered once its guard is dropped
+    assert!(!registry.cancel("job_id"));
+}
+
+#[tokio::test]
+async fn test_batch_limits_split() {
+    let batch: Vec<StringRecord> = vec![
+        StringRecord::from(vec!["a", "b"]),
+        StringRecord::from(vec!["c", "d"]),
+        StringRecord::from(vec!["e", "f"]),
+        StringRecord::from(vec!["g".repeat(200), "h".into()]),
+        StringRecord::from(vec!["i", "j"]),
+    ];
+
+    // Two rows of two values fill the parameters, the wide row is alone once the bytes are exceeded
+    let limits: BatchLimits = BatchLimits::new(4, 100);
+    let statements: Vec<&[StringRecord]> = limits.split(&batch);
+    let sizes: Vec<usize> = statements.iter().map(|rows| rows.len()).collect();
+    assert_eq!(sizes, vec![2, 1, 1, 1]);
+    assert_eq!(statements[2][0][1], *"h");
+
+    let limits: BatchLimits = BatchLimits::new(65_535, usize::MAX);
+    assert_eq!(limits.split(&batch).len(), 1);
+    assert!(!limits.exceeds(65_535, usize::MAX));
+    assert!(limits.exceeds(65_536, 1));
+    assert!(limits.split(&[]).is_empty());
+
+    assert_eq!(BatchLimits::get_record_size(&batch[0]), 34);
+    assert_eq!(
+        BatchLimits::for_engine(&DatabaseEngine::SQLite).max_parameters,
+        32_766
+    );
+    assert_eq!(
+        BatchLimits::for_engine(&DatabaseEngine::MySQL)
+            .with_max_packet(64 * 1024 * 1024)
+            .max_bytes,
+        64 * 1024 * 1024 - 1024
+    );
+}
+
+#[tokio::test]
+async fn test_file_section_skips_preamble_and_footer() {
+    let csv_file_path: String = generate_csv_file_with_content(
+        "test_file_section",
+        "Exported on 2024-01-01\n\nid,name\n1,a\n2,b\nTotal: 2 rows\n",
+    )
+    .expect("Failed to generate csv file");
+
+    let mut section: FileSection =
+        FileSection::open(&csv_file_path, 2, 1).expect("Failed to open file section");
+    let mut content: String = String::new();
+    section
+        .read_to_string(&mut content)
+        .expect("Failed to read file section");
+    assert_eq!(content, "id,name\n1,a\n2,b\n");
+
+    // Seeking is relative to the section, so that csv positions stay consistent
+    section
+        .seek(SeekFrom::Start(8))
+        .expect("Failed to seek in file section");
+    let mut reader: Reader<FileSection> = Reader::from_reader(section);
+    let records: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(records, vec![StringRecord::from(vec!["2", "b"])]);
+
+    // A footer without a final line terminator, and more skipped lines than the file has
+    let csv_file_path: String =
+        generate_csv_file_with_content("test_file_section", "id,name\n1,a\nTotal")
+            .expect("Failed to generate csv file");
+    let mut content: String = String::new();
+    FileSection::open(&csv_file_path, 0, 1)
+        .expect("Failed to open file section")
+        .read_to_string(&mut content)
+        .expect("Failed to read file section");
+    assert_eq!(content, "id,name\n1,a\n");
+    assert_eq!(
+        FileSection::open(&csv_file_path, 5, 1)
+            .expect("Failed to open file section")
+            .len(),
+        0
+    );
+
+    remove_csv_file("test_file_section").expect("Failed to remove csv file");
 }
@@ -1,5 +1,9 @@
+use crate::fileflow::enumeration::conflict_policy::ConflictPolicy;
 use crate::fileflow::enumeration::database_engine::DatabaseEngine;
+use crate::fileflow::enumeration::insertion_type::InsertionType;
+use crate::fileflow::enumeration::reject_format::RejectFormat;
 use crate::fileflow::stuct::db_config::DbConfig;
+use crate::fileflow::stuct::insert_config::InsertConfig;
 use crate::fileflow::stuct::save_config::SaveConfig;
 use csv::Writer;
 use std::error::Error;
@@ -9,6 +13,7 @@ use std::path::PathBuf;
 /// Get a test PostgreSQL configuration
 pub fn get_test_pg_config() -> DbConfig {
     DbConfig {
+        config_name: String::new(),
         db_driver: DatabaseEngine::Postgres,
         username: "postgres".into(),
         password: String::from("password"),
@@ -22,6 +27,7 @@ pub fn get_test_pg_config() -> DbConfig {
 /// Get a test SQLite configuration
 pub fn get_test_sqlite_config(str: String) -> DbConfig {
     DbConfig {
+        config_name: String::new(),
         db_driver: DatabaseEngine::SQLite,
         username: String::new(),
         password: String::new(),
@@ -39,6 +45,7 @@ pub fn get_test_sqlite_config(str: String) -> DbConfig {
 /// Get a test MySQL configuration
 pub fn get_test_mysql_config() -> DbConfig {
     DbConfig {
+        config_name: String::new(),
         db_driver: DatabaseEngine::MySQL,
         username: "root".into(),
         password: "password".into(),
@@ -52,6 +59,7 @@ pub fn get_test_mysql_config() -> DbConfig {
 /// Get a test MariaDB configuration
 pub fn get_test_maridb_config() -> DbConfig {
     DbConfig {
+        config_name: String::new(),
         db_driver: DatabaseEngine::MariaDB,
         username: String::from("root"),
         password: String::from("password"),
@@ -75,6 +83,37 @@ pub fn get_test_save_config(config_name: &str) -> SaveConfig {
     }
 }
 
+/// Get a test import configuration of the given file into `test_table`, every option left to its default
+pub fn get_test_insert_config(
+    file_path: &str,
+    mode: InsertionType,
+    db_driver: DatabaseEngine,
+) -> InsertConfig {
+    InsertConfig {
+        file_path: file_path.into(),
+        table_name: "test_table".into(),
+        schema: None,
+        mode,
+        db_driver,
+        key_columns: Vec::new(),
+        conflict_policy: ConflictPolicy::default(),
+        bulk_load: false,
+        transactional: false,
+        reject_format: RejectFormat::default(),
+        job_id: String::new(),
+        workers: 0,
+        has_headers: None,
+        column_names: Vec::new(),
+        preamble_lines: 0,
+        footer_lines: 0,
+        varchar_length_unit: None,
+        varchar_max_length: None,
+        varchar_padding: None,
+        optimized_strategy: None,
+        add_new_columns: false,
+    }
+}
+
 /// Delete the configuration file with the given file name
 pub fn delete_config_file(config_file_name: &str) -> Result<(), Box<dyn Error>> {
     if !std::path::Path::new(&config_file_name).exists() {
@@ -153,6 +192,25 @@ pub fn generate_csv_file(file_name: &str) -> Result<String, Box<dyn Error>> {
     Ok(csv_file_path)
 }
 
+/// Generate a CSV file with the given file name and raw content and return the file path
+pub fn generate_csv_file_with_content(
+    file_name: &str,
+    content: &str,
+) -> Result<String, Box<dyn Error>> {
+    let absolute_path: PathBuf =
+        std::env::current_exe().expect("Failed to get current executable path");
+    let path: &str = absolute_path
+        .parent()
+        .expect("Failed to get parent directory")
+        .to_str()
+        .expect("Failed to convert path to string");
+
+    let csv_file_path: String = format!("{path}/{file_name}.csv");
+    std::fs::write(&csv_file_path, content).expect("Failed to write CSV file");
+
+    Ok(csv_file_path)
+}
+
 /// Remove the CSV file with the given file name if it exists
 pub fn remove_csv_file(file_name: &str) -> Result<(), Box<dyn Error>> {
     let absolute_path: PathBuf =
@@ -1,5 +1,6 @@
 import React, {useEffect, useState} from 'react';
 import {invoke} from "@tauri-apps/api/core";
+import {listen, UnlistenFn} from "@tauri-apps/api/event";
 import {Label} from "@/components/ui/label.tsx";
 import {log_error, requestAllTablesFromConnection} from "@/components/hooks/utils.tsx";
 import {Select, SelectContent, SelectItem, SelectTrigger} from "@/components/ui/select.tsx";
@@ -11,6 +12,8 @@ import ConnectionForm from "@/components/hooks/database/ConnectionForm.tsx";
 import {AnimatePresence, motion} from 'framer-motion';
 import {DatabaseConfig} from "@/interfaces/DatabaseConfig.tsx";
 import {CheckBoxCombo} from "@/components/hooks/component/CheckBoxCombo.tsx";
+import {Progress} from "@/interfaces/Progress.tsx";
+import {ExportReport} from "@/interfaces/ExportReport.tsx";
 
 const Download: React.FC = () => {
 
@@ -52,6 +55,13 @@ const Download: React.FC = () => {
         }
 
         const handleDownload = async () => {
+            const jobId: string = crypto.randomUUID();
+            const unlisten: UnlistenFn = await listen<Progress>('export-progress', (event) => {
+                toast.loading(`${event.payload.table_name}: ${event.payload.rows_processed} rows exported`, {
+                    id: 'export-progress',
+                    action: {label: 'Cancel', onClick: () => invoke('cancel_job', {jobId})},
+                });
+            });
             try {
 
                 if (!selectedTables && absolutePath === "") {
@@ -65,23 +75,27 @@ const Download: React.FC = () => {
 
                 setShowLoader(true);
 
-                const download_table_response: string = await invoke<string>('download_table', {
+                const report: ExportReport = await invoke<ExportReport>('download_table', {
                     config: {
                         table_name_list: selectedTables,
                         location: absolutePath,
-                        separator: getSeparatorName(separator).toLocaleLowerCase()
+                        separator: getSeparatorName(separator).toLocaleLowerCase(),
+                        job_id: jobId
                     }
                 });
 
-                if (!download_table_response.startsWith("Table")) {
-                    throw new Error(download_table_response);
+                if (report.tables.length === 0) {
+                    throw new Error([report.summary, ...report.failures].join('\n'));
                 }
 
-                toast.success(download_table_response);
+                report.failures.forEach((failure) => toast.warning(failure));
+                toast.success(report.summary);
             } catch (error) {
                 log_error(error)
             }
 
+            unlisten();
+            toast.dismiss('export-progress');
             setShowLoader(false);
         }
 
@@ -2,10 +2,13 @@ import React from 'react';
 import {Eraser, Upload} from "lucide-react";
 import {toast} from "sonner";
 import {invoke} from "@tauri-apps/api/core";
+import {listen, UnlistenFn} from "@tauri-apps/api/event";
 import {Tooltip, TooltipContent, TooltipProvider, TooltipTrigger} from "@/components/ui/tooltip.tsx";
 import {log_error} from "@/components/hooks/utils.tsx";
 import {DatabaseConfig} from "@/interfaces/DatabaseConfig.tsx";
 import {InsertionType} from "@/components/fileflowui/load/insert/Insert.tsx";
+import {Progress} from "@/interfaces/Progress.tsx";
+import {ImportReport} from "@/interfaces/ImportReport.tsx";
 
 interface ButtonGroupProps {
     dbConfig: DatabaseConfig;
@@ -24,6 +27,17 @@ const ButtonGroupAction: React.FC<ButtonGroupProps> = (props: ButtonGroupProps)
 
     const handleInsert = async (e: React.FormEvent) => {
         e.preventDefault();
+        let jobId: string = crypto.randomUUID();
+        let csv: Record<string, string> | undefined;
+        const unlisten: UnlistenFn = await listen<Progress>('import-progress', (event) => {
+            const progress: Progress = event.payload;
+            const percent: number = progress.total_bytes > 0 ? Math.floor(progress.bytes_read * 100 / progress.total_bytes) : 0;
+            const remaining: string = progress.estimated_remaining_seconds !== null ? `, ${progress.estimated_remaining_seconds}s left` : '';
+            toast.loading(`${progress.table_name}: ${percent}% (${progress.rows_processed} rows, ${progress.rows_rejected} rejected${remaining})`, {
+                id: 'import-progress',
+                action: {label: 'Cancel', onClick: () => invoke('cancel_job', {jobId})},
+            });
+        });
         try {
             if (!props.filesPath) {
                 toast.warning('Please select a file');
@@ -41,24 +55,35 @@ const ButtonGroupAction: React.FC<ButtonGroupProps> = (props: ButtonGroupProps)
             const tableName: string[] = props.tableName.split(',').map((name) => name.trim());
 
             for (const [index, file] of props.filesPath.entries()) {
-                const insert_csv_data_response: string = await invoke<string>('insert_csv_data', {
-                    csv: {
-                        table_name: tableName[index],
-                        file_path: file,
-                        db_driver: props.dbConfig.db_driver.toLowerCase(),
-                        mode: props.mode,
-                    },
-                });
-
-                if (insert_csv_data_response.startsWith("Error:")) {
-                    log_error(insert_csv_data_response);
-                }
-                toast.success(insert_csv_data_response);
+                jobId = crypto.randomUUID();
+                csv = {
+                    table_name: tableName[index],
+                    file_path: file,
+                    db_driver: props.dbConfig.db_driver.toLowerCase(),
+                    mode: props.mode,
+                    job_id: jobId,
+                };
+                const report: ImportReport = await invoke<ImportReport>('insert_csv_data', {csv});
+
+                report.warnings.forEach((warning) => toast.warning(warning));
+                toast.success(report.summary);
             }
 
         } catch (error) {
             log_error(error);
+            // Only a resumable mode keeps its committed batches, the import can then continue from its last checkpoint
+            if (csv && await invoke<boolean>('can_resume_import', {csv}).catch(() => false)) {
+                const interrupted: Record<string, string> = {...csv, job_id: crypto.randomUUID()};
+                toast.error(`Import of ${interrupted.file_path} interrupted`, {
+                    action: {
+                        label: 'Resume',
+                        onClick: () => invoke<ImportReport>('resume_import', {csv: interrupted}).then((report) => toast.success(report.summary)).catch(log_error),
+                    },
+                });
+            }
         }
+        unlisten();
+        toast.dismiss('import-progress');
         props.setShowLoader(false);
     };
 
@@ -14,6 +14,7 @@ import {DatabaseConfig} from "@/interfaces/DatabaseConfig.tsx";
 export enum InsertionType {
     Fast = "fast",
     Optimized = "optimized",
+    Append = "append",
 }
 
 const Insert: React.FC = () => {
@@ -13,7 +13,7 @@ const ModeSelection: React.FC<ModeSelectionProps> = (props: ModeSelectionProps)
         <div className="flex justify-center mt-10">
             <RadioGroup defaultValue="fast" className="flex justify-center gap-10"
                         onValueChange={(e: string): void => {
-                            if (e === InsertionType.Fast || e === InsertionType.Optimized) {
+                            if (e === InsertionType.Fast || e === InsertionType.Optimized || e === InsertionType.Append) {
                                 props.setMode(e);
                             }
                         }
@@ -48,6 +48,21 @@ const ModeSelection: React.FC<ModeSelectionProps> = (props: ModeSelectionProps)
                     </Tooltip>
                 </TooltipProvider>
 
+                <TooltipProvider>
+                    <Tooltip>
+                        <TooltipTrigger>
+                            <div className="space-x-2">
+                                <RadioGroupItem value={InsertionType.Append} id="r3"/>
+                                <Label htmlFor="r3">Append</Label>
+                            </div>
+                        </TooltipTrigger>
+                        <TooltipContent>
+                            Append inserts the rows into an existing table without dropping it. The CSV headers
+                            must match the columns of the table.
+                        </TooltipContent>
+                    </Tooltip>
+                </TooltipProvider>
+
             </RadioGroup>
         </div>
     );
@@ -44,7 +44,8 @@ const ConnectionForm: React.FC<DatabaseFormProps> = (props: DatabaseFormProps) =
                     props.dbConfig.username,
                     props.dbConfig.password,
                     props.dbConfig.db_name,
-                    props.dbConfig.sqlite_file_path
+                    props.dbConfig.sqlite_file_path,
+                    props.dbConfig.config_name
                 );
 
                 if (typeof connect_to_database_response !== "boolean") {
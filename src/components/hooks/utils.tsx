@@ -95,7 +95,7 @@ export const disconnect_from_database = async () => {
 }
 
 // Connect to the database and disconnect if already connected for the user
-export const connect_to_database = async (db_driver: string, db_host: string, port: string, username: string, password: string, db_name: string, sqlite_file_path: string): Promise<void | boolean> => {
+export const connect_to_database = async (db_driver: string, db_host: string, port: string, username: string, password: string, db_name: string, sqlite_file_path: string, config_name: string = ''): Promise<void | boolean> => {
     try {
         const is_connected_response: string | boolean = await is_connected();
 
@@ -118,6 +118,7 @@ export const connect_to_database = async (db_driver: string, db_host: string, po
                 password: password,
                 db_name: db_name,
                 sqlite_file_path: sqlite_file_path,
+                config_name: config_name,
             },
         });
 
@@ -0,0 +1,14 @@
+export interface TableExport {
+    table_name: string;
+    file_path: string;
+    rows_exported: number;
+    bytes_written: number;
+}
+
+export interface ExportReport {
+    tables_requested: number;
+    tables: TableExport[];
+    failures: string[];
+    duration_ms: number;
+    summary: string;
+}
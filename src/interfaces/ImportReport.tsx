@@ -0,0 +1,32 @@
+import {SchemaDrift} from "@/interfaces/SchemaDrift.tsx";
+
+export interface BatchFailure {
+    first_line: number | null;
+    row_count: number;
+    error: string;
+}
+
+export interface CsvDialect {
+    separator: string;
+    quote: string;
+    escape: string | null;
+    has_headers: boolean;
+}
+
+export interface ImportReport {
+    table_name: string;
+    mode: string;
+    rows_read: number;
+    rows_inserted: number;
+    rows_rejected: number;
+    rows_resumed: number;
+    bytes_processed: number;
+    duration_ms: number;
+    batch_failures: BatchFailure[];
+    dialect: CsvDialect;
+    ddl: string[];
+    warnings: string[];
+    schema_drift: SchemaDrift | null;
+    reject_path: string | null;
+    summary: string;
+}
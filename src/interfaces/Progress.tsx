@@ -0,0 +1,8 @@
+export interface Progress {
+    table_name: string;
+    bytes_read: number;
+    total_bytes: number;
+    rows_processed: number;
+    rows_rejected: number;
+    estimated_remaining_seconds: number | null;
+}
@@ -0,0 +1,21 @@
+export interface RenamedColumn {
+    table_column: string;
+    csv_column: string;
+    similarity: number;
+}
+
+export interface IncompatibleColumn {
+    table_column: string;
+    csv_column: string;
+    table_type: string;
+    csv_type: string | Record<string, unknown>;
+}
+
+export interface SchemaDrift {
+    table_name: string;
+    table_exists: boolean;
+    added: string[];
+    missing: string[];
+    renamed: RenamedColumn[];
+    incompatible: IncompatibleColumn[];
+}